@@ -1,11 +1,16 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 
 use bevy::prelude::*;
 use clap::{Parser, Subcommand};
 use simgine_core::{
+    asset_manifest::{lint::LintManifests, schema::ExportSchema},
     network::{Connect, DEFAULT_PORT, Host},
+    replay::{PlayReplay, StartRecording},
     state::GameState,
-    world::LoadWorld,
+    world::{LoadWorld, time::speed::SpeedPolicy},
 };
 
 /// Logic for command line interface.
@@ -24,12 +29,29 @@ fn apply_command(mut commands: Commands, mut cli: ResMut<Cli>) {
 
     match command {
         GameCommand::FamilyEditor => commands.set_state(GameState::FamilyEditor),
-        GameCommand::Load { name } => commands.trigger(LoadWorld { name }),
-        GameCommand::Host { name, port } => {
+        GameCommand::Load { name, record } => {
+            if let Some(path) = record {
+                commands.trigger(StartRecording { path });
+            }
+            commands.trigger(LoadWorld { name });
+        }
+        GameCommand::Host {
+            name,
+            port,
+            speed_policy,
+            record,
+        } => {
+            if let Some(path) = record {
+                commands.trigger(StartRecording { path });
+            }
+            commands.insert_resource(speed_policy);
             commands.trigger(LoadWorld { name });
             commands.trigger(Host { port })
         }
         GameCommand::Join { ip, port } => commands.trigger(Connect { ip, port }),
+        GameCommand::Playback { path } => commands.trigger(PlayReplay { path }),
+        GameCommand::Lint => commands.trigger(LintManifests),
+        GameCommand::Schema { path } => commands.trigger(ExportSchema { path }),
     }
 }
 
@@ -41,6 +63,16 @@ pub(crate) struct Cli {
     command: Option<GameCommand>,
 }
 
+impl Cli {
+    /// Returns `true` if the command doesn't need a window.
+    pub(crate) fn is_headless(&self) -> bool {
+        matches!(
+            self.command,
+            Some(GameCommand::Playback { .. } | GameCommand::Lint | GameCommand::Schema { .. })
+        )
+    }
+}
+
 impl Default for Cli {
     fn default() -> Self {
         Self::parse()
@@ -53,6 +85,10 @@ enum GameCommand {
     Load {
         /// World name to load.
         name: String,
+
+        /// Record the session into a replay file.
+        #[clap(short, long)]
+        record: Option<PathBuf>,
     },
     Host {
         /// World name to load.
@@ -61,6 +97,14 @@ enum GameCommand {
         /// Port to use.
         #[clap(short, long, default_value_t = DEFAULT_PORT)]
         port: u16,
+
+        /// How speed requests from players are combined: 'host-only', 'majority' or 'slowest'.
+        #[clap(short, long, default_value = "host-only")]
+        speed_policy: SpeedPolicy,
+
+        /// Record the session into a replay file.
+        #[clap(short, long)]
+        record: Option<PathBuf>,
     },
     Join {
         /// Server IP address.
@@ -71,4 +115,16 @@ enum GameCommand {
         #[clap(short, long, default_value_t = DEFAULT_PORT)]
         port: u16,
     },
+    /// Replay a recorded session without a window and verify its checksums.
+    Playback {
+        /// Replay file to play.
+        path: PathBuf,
+    },
+    /// Check all asset manifests for errors without a window.
+    Lint,
+    /// Export JSON Schema for object manifests without a window.
+    Schema {
+        /// File to write the schema to, prints to stdout if not set.
+        path: Option<PathBuf>,
+    },
 }
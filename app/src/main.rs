@@ -1,58 +1,104 @@
 mod cli;
 mod window_name;
 
+use std::time::Duration;
+
 use avian3d::prelude::*;
 use bevy::{
+    app::{PluginGroupBuilder, ScheduleRunnerPlugin},
     gltf::{GltfPlugin, convert_coordinates::GltfConvertCoordinates},
+    log::LogPlugin,
     prelude::*,
-    render::RenderPlugin,
+    render::{RenderPlugin, settings::WgpuSettings},
+    window::ExitCondition,
+    winit::WinitPlugin,
 };
 use bevy_enhanced_input::EnhancedInputPlugin;
 use bevy_mod_outline::OutlinePlugin;
 use bevy_replicon::prelude::*;
 use bevy_replicon_renet::RepliconRenetPlugins;
-use simgine_core::SimgineCorePlugin;
+use simgine_core::{SimgineCorePlugin, mods};
 use simgine_ui::SimgineUiPlugin;
 
+use cli::Cli;
+
 fn main() {
     let mut app = App::new();
-    app.add_plugins((cli::plugin, window_name::plugin))
-        .add_plugins((
-            DefaultPlugins
-                .set(RenderPlugin {
-                    synchronous_pipeline_compilation: true,
+    // Added separately before other plugins to see messages from mod discovery.
+    app.add_plugins((LogPlugin::default(), cli::plugin, mods::plugin));
+
+    let headless = app.world().resource::<Cli>().is_headless();
+    if headless {
+        app.add_plugins((
+            default_plugins(headless)
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
                     ..Default::default()
                 })
-                .set(GltfPlugin {
-                    convert_coordinates: GltfConvertCoordinates {
-                        rotate_scene_entity: true,
-                        rotate_meshes: true,
-                    },
-                    ..Default::default()
-                }),
-            EnhancedInputPlugin,
-            RepliconPlugins.set(ServerPlugin {
-                tick_schedule: None,
-                ..Default::default()
-            }),
-            RepliconRenetPlugins,
-            PhysicsPlugins::default()
-                .build()
-                .disable::<ColliderTransformPlugin>()
-                .disable::<IntegratorPlugin>()
-                .disable::<SolverPlugin>()
-                .disable::<CcdPlugin>()
-                .disable::<IslandPlugin>()
-                .disable::<IslandSleepingPlugin>()
-                .disable::<JointPlugin>()
-                .disable::<MassPropertyPlugin>()
-                .disable::<ForcePlugin>()
-                .disable::<SpatialQueryPlugin>()
-                .disable::<PhysicsInterpolationPlugin>(),
-            OutlinePlugin::EXTRUDE_VERTEX,
-            SimgineCorePlugin,
-            SimgineUiPlugin,
+                .disable::<WinitPlugin>(),
+            ScheduleRunnerPlugin::run_loop(Duration::ZERO),
         ));
+    } else {
+        app.add_plugins((default_plugins(headless), window_name::plugin));
+    }
+
+    app.add_plugins((
+        EnhancedInputPlugin,
+        RepliconPlugins.set(ServerPlugin {
+            tick_schedule: None,
+            ..Default::default()
+        }),
+        RepliconRenetPlugins,
+        PhysicsPlugins::default()
+            .build()
+            .disable::<ColliderTransformPlugin>()
+            .disable::<IntegratorPlugin>()
+            .disable::<SolverPlugin>()
+            .disable::<CcdPlugin>()
+            .disable::<IslandPlugin>()
+            .disable::<IslandSleepingPlugin>()
+            .disable::<JointPlugin>()
+            .disable::<MassPropertyPlugin>()
+            .disable::<ForcePlugin>()
+            .disable::<SpatialQueryPlugin>()
+            .disable::<PhysicsInterpolationPlugin>(),
+        OutlinePlugin::EXTRUDE_VERTEX,
+        SimgineCorePlugin,
+    ));
+
+    if !headless {
+        app.add_plugins(SimgineUiPlugin);
+    }
 
     app.run();
 }
+
+fn default_plugins(headless: bool) -> PluginGroupBuilder {
+    let render_plugin = if headless {
+        RenderPlugin {
+            render_creation: WgpuSettings {
+                backends: None,
+                ..Default::default()
+            }
+            .into(),
+            ..Default::default()
+        }
+    } else {
+        RenderPlugin {
+            synchronous_pipeline_compilation: true,
+            ..Default::default()
+        }
+    };
+
+    DefaultPlugins
+        .set(render_plugin)
+        .set(GltfPlugin {
+            convert_coordinates: GltfConvertCoordinates {
+                rotate_scene_entity: true,
+                rotate_meshes: true,
+            },
+            ..Default::default()
+        })
+        .disable::<LogPlugin>()
+}
@@ -0,0 +1,120 @@
+use std::mem;
+
+use bevy::{
+    ecs::{entity::MapEntities, system::SystemState},
+    prelude::*,
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use simgine_core::undo::{
+    EntityRecorder, HistoryCommands, ReversibleCommand, bench, history::CommandHistory,
+};
+
+fn remapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remapping");
+    for history_len in [100, 1_000, 10_000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(history_len),
+            &history_len,
+            |b, &history_len| {
+                let mut app = App::new();
+                app.add_plugins((MinimalPlugins, bench::plugin))
+                    .insert_resource(CommandHistory::new(history_len + 1));
+
+                let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+                let mut commands = state.get_mut(app.world_mut()).unwrap();
+                for _ in 0..history_len {
+                    let entity = commands.spawn(Transform::default()).id();
+                    bench::queue_reversible(
+                        &mut commands,
+                        Translate {
+                            entity,
+                            translation: Vec3::ONE,
+                        },
+                    );
+                }
+                state.apply(app.world_mut());
+
+                let mut entity = app.world_mut().spawn(Transform::default()).id();
+                b.iter(|| {
+                    // Undo re-creates the entity, which requires remapping.
+                    let mut commands = state.get_mut(app.world_mut()).unwrap();
+                    bench::queue_reversible(&mut commands, Despawn { entity });
+                    commands.undo();
+                    state.apply(app.world_mut());
+
+                    let mut transforms = app
+                        .world_mut()
+                        .query_filtered::<Entity, (With<Transform>, With<Recreated>)>();
+                    entity = transforms.single(app.world()).unwrap();
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+#[derive(MapEntities)]
+struct Translate {
+    #[entities]
+    entity: Entity,
+    translation: Vec3,
+}
+
+impl ReversibleCommand for Translate {
+    fn apply(
+        self: Box<Self>,
+        _recorder: &mut EntityRecorder,
+        world: &mut World,
+    ) -> Option<Box<dyn ReversibleCommand>> {
+        let mut transform = world.get_mut::<Transform>(self.entity)?;
+        let original_translation = mem::replace(&mut transform.translation, self.translation);
+
+        Some(Box::new(Self {
+            entity: self.entity,
+            translation: original_translation,
+        }))
+    }
+}
+
+#[derive(MapEntities)]
+struct Spawn;
+
+impl ReversibleCommand for Spawn {
+    fn apply(
+        self: Box<Self>,
+        recorder: &mut EntityRecorder,
+        world: &mut World,
+    ) -> Option<Box<dyn ReversibleCommand>> {
+        let entity = world.spawn((Transform::default(), Recreated)).id();
+        bench::record(recorder, entity);
+
+        Some(Box::new(Despawn { entity }))
+    }
+}
+
+#[derive(MapEntities)]
+struct Despawn {
+    #[entities]
+    entity: Entity,
+}
+
+impl ReversibleCommand for Despawn {
+    fn apply(
+        self: Box<Self>,
+        recorder: &mut EntityRecorder,
+        world: &mut World,
+    ) -> Option<Box<dyn ReversibleCommand>> {
+        world.get_entity_mut(self.entity).ok()?.despawn();
+        bench::record(recorder, self.entity);
+
+        Some(Box::new(Spawn))
+    }
+}
+
+/// Marks the entity spawned by [`Spawn`].
+#[derive(Component)]
+struct Recreated;
+
+criterion_group!(benches, remapping);
+criterion_main!(benches);
@@ -1,80 +1,244 @@
+pub mod appearance;
+pub mod category;
+pub mod lint;
+pub mod locale;
 pub mod object;
 mod reflected_component;
+pub mod schema;
+pub mod surface;
 
-use std::{any::TypeId, env, marker::PhantomData, path::Path};
+use std::{any::TypeId, marker::PhantomData, path::PathBuf};
 
 use bevy::{
     asset::{
         AssetLoader, AssetPath, AsyncReadExt, LoadContext, LoadState, LoadedUntypedAsset,
-        io::Reader,
+        io::{AssetSourceId, Reader},
     },
+    platform::collections::HashMap,
     prelude::*,
-    reflect::{TypeRegistry, TypeRegistryArc, serde::TypedReflectDeserializer},
+    reflect::{
+        DynamicStruct, NamedField, ReflectFromReflect, ReflectMut, ReflectRef, Struct, StructInfo,
+        TypeInfo, TypeRegistry, TypeRegistryArc, serde::TypedReflectDeserializer,
+    },
+    tasks::{
+        IoTaskPool, Task, block_on,
+        futures_lite::{StreamExt, future},
+    },
 };
 use serde::{Deserialize, de::DeserializeSeed};
-use walkdir::WalkDir;
 
-use crate::state::GameState;
+use crate::{error_event::trigger_error, mods::Mods, state::GameState};
+use appearance::AppearanceManifest;
+use category::CategoryManifest;
+use locale::LocaleManifest;
 use object::ObjectManifest;
+use reflected_component::ReflectedComponent;
+use surface::SurfaceManifest;
 
 pub(super) fn plugin(app: &mut App) {
-    app.init_asset::<ObjectManifest>()
+    app.add_plugins((lint::plugin, schema::plugin))
+        .init_asset::<ObjectManifest>()
+        .init_asset::<CategoryManifest>()
+        .init_asset::<AppearanceManifest>()
+        .init_asset::<SurfaceManifest>()
+        .init_asset::<LocaleManifest>()
         .init_asset_loader::<ManifestLoader<ObjectManifest>>()
+        .init_asset_loader::<ManifestLoader<CategoryManifest>>()
+        .init_asset_loader::<ManifestLoader<AppearanceManifest>>()
+        .init_asset_loader::<ManifestLoader<SurfaceManifest>>()
+        .init_asset_loader::<ManifestLoader<LocaleManifest>>()
+        // Mods are registered before `AssetPlugin` by `mods::plugin`,
+        // without it only the base assets are used.
+        .init_resource::<Mods>()
         .init_resource::<AssetManifests>()
+        .init_resource::<ManifestPaths>()
+        .init_resource::<ManifestsProgress>()
+        .add_systems(OnEnter(GameState::ManifestsLoading), start_discovery)
         .add_systems(
             Update,
-            wait_for_loading.run_if(in_state(GameState::ManifestsLoading)),
+            (
+                finish_discovery.run_if(resource_exists::<ManifestDiscovery>),
+                wait_for_loading
+                    .pipe(trigger_error)
+                    .run_if(not(resource_exists::<ManifestDiscovery>)),
+            )
+                .chain()
+                .run_if(in_state(GameState::ManifestsLoading)),
         );
 }
 
+fn start_discovery(mut commands: Commands, asset_server: Res<AssetServer>, mods: Res<Mods>) {
+    let mut sources = vec![AssetSourceId::Default];
+    sources.extend(
+        mods.iter()
+            .map(|mod_info| AssetSourceId::Name(mod_info.name.clone().into())),
+    );
+
+    let asset_server = asset_server.clone();
+    let task = IoTaskPool::get().spawn(async move {
+        // Later sources override manifests with the same relative path.
+        let mut paths = HashMap::new();
+        for source in sources {
+            if let Err(e) = find_manifests(&mut paths, &asset_server, source.clone()).await {
+                error!("unable to search for manifests in '{source}': {e}");
+            }
+        }
+
+        paths
+    });
+
+    commands.insert_resource(ManifestDiscovery(task));
+}
+
+async fn find_manifests(
+    paths: &mut HashMap<PathBuf, AssetSourceId<'static>>,
+    asset_server: &AssetServer,
+    source: AssetSourceId<'static>,
+) -> Result<()> {
+    debug!("searching for manifests in '{source}'");
+
+    let reader = asset_server.get_source(source.clone())?.reader();
+    let mut dirs = vec![PathBuf::new()];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = reader.read_directory(&dir).await?;
+        while let Some(path) = entries.next().await {
+            if reader.is_directory(&path).await? {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|e| e == "ron")
+                && !path
+                    .to_string_lossy()
+                    .ends_with(&format!(".{TEMPLATE_EXTENSION}"))
+                && let Some(previous) = paths.insert(path.clone(), source.clone())
+            {
+                debug!("'{source}' overrides {path:?} from '{previous}'");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn finish_discovery(
+    mut commands: Commands,
+    mut discovery: ResMut<ManifestDiscovery>,
+    mut manifests: ResMut<AssetManifests>,
+    mut progress: ResMut<ManifestsProgress>,
+    asset_server: Res<AssetServer>,
+) {
+    let Some(paths) = block_on(future::poll_once(&mut discovery.0)) else {
+        return;
+    };
+
+    info!("found {} asset manifests", paths.len());
+    commands.remove_resource::<ManifestDiscovery>();
+    progress.total = Some(paths.len());
+
+    for (path, source) in &paths {
+        let path = AssetPath::from(path.clone()).with_source(source.clone());
+        debug!("loading manifest '{path}'");
+        let handle = asset_server.load_builder().load_untyped(path);
+        manifests.push(handle);
+    }
+
+    commands.insert_resource(ManifestPaths(paths));
+}
+
 fn wait_for_loading(
     mut commands: Commands,
+    mut progress: ResMut<ManifestsProgress>,
     manifests: Res<AssetManifests>,
     asset_server: Res<AssetServer>,
-) {
-    if manifests
-        .iter()
-        .all(|h| !matches!(asset_server.load_state(h), LoadState::Loading))
-    {
-        info!("finished loading asset manifests");
-        commands.set_state(GameState::Menu);
+) -> Result<()> {
+    let mut loaded = 0;
+    let mut errors = Vec::new();
+    for handle in manifests.iter() {
+        match asset_server.load_state(handle) {
+            LoadState::Loaded => loaded += 1,
+            LoadState::Failed(e) => errors.push(e.to_string()),
+            LoadState::NotLoaded | LoadState::Loading => (),
+        }
+    }
+
+    let total = progress.total;
+    progress.set_if_neq(ManifestsProgress {
+        total,
+        loaded,
+        failed: errors.len(),
+    });
+
+    if loaded + errors.len() < manifests.len() {
+        return Ok(());
+    }
+
+    info!("finished loading asset manifests");
+    commands.set_state(GameState::Menu);
+
+    if !errors.is_empty() {
+        return Err(format!(
+            "unable to load {} out of {} manifests:\n{}",
+            errors.len(),
+            manifests.len(),
+            errors.join("\n")
+        )
+        .into());
     }
+
+    Ok(())
 }
 
 /// Resource that keeps manifests loaded.
-#[derive(Resource, Deref, DerefMut)]
+#[derive(Resource, Default, Deref, DerefMut)]
 struct AssetManifests(Vec<Handle<LoadedUntypedAsset>>);
 
-impl FromWorld for AssetManifests {
-    fn from_world(world: &mut World) -> Self {
-        let current_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
-        let assets_dir = Path::new(&current_dir).join("assets");
-        debug!("searching for manifests in {assets_dir:?}");
-
-        let mut manifests = AssetManifests(Vec::new());
-        let asset_server = world.resource::<AssetServer>();
-        for path in WalkDir::new(&assets_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .map(|e| e.into_path())
-            .filter(|p| p.extension().is_some_and(|e| e == "ron"))
-        {
-            let relative_path = path
-                .strip_prefix(&assets_dir)
-                .unwrap_or_else(|e| panic!("entries should start with {assets_dir:?}: {e}"));
+/// Task that searches for manifests in all asset sources.
+#[derive(Resource)]
+struct ManifestDiscovery(Task<HashMap<PathBuf, AssetSourceId<'static>>>);
 
-            debug!("loading manifest {relative_path:?}");
+/// Sources that provide discovered manifests by their paths.
+///
+/// Mods can override manifests with the same relative path, so references to
+/// manifests, such as [`Object::manifest`](crate::world::object::Object::manifest),
+/// store paths without a source. This way saves keep working when mods are added
+/// or removed.
+#[derive(Resource, Default)]
+pub struct ManifestPaths(HashMap<PathBuf, AssetSourceId<'static>>);
+
+impl ManifestPaths {
+    /// Returns the path without a source that should be used to reference a manifest.
+    pub fn canonical(path: &AssetPath) -> AssetPath<'static> {
+        path.clone_owned().with_source(AssetSourceId::Default)
+    }
 
-            let handle = asset_server
-                .load_builder()
-                .load_untyped(AssetPath::from_path(relative_path));
-            manifests.push(handle);
+    /// Returns the path from the source that provides the manifest.
+    ///
+    /// Unknown paths are returned as is.
+    pub fn resolve(&self, path: &AssetPath) -> AssetPath<'static> {
+        let path = path.clone_owned();
+        match self.0.get(path.path()) {
+            Some(source) => path.with_source(source.clone()),
+            None => path,
         }
+    }
 
-        manifests
+    /// Returns the handle of a loaded manifest by its canonical path.
+    pub fn get_handle<A: Asset>(
+        &self,
+        asset_server: &AssetServer,
+        path: &AssetPath,
+    ) -> Option<Handle<A>> {
+        asset_server.get_handle(self.resolve(path))
     }
 }
 
+/// Progress of loading asset manifests.
+#[derive(Resource, Default, PartialEq, Clone, Copy)]
+pub struct ManifestsProgress {
+    /// Number of found manifests, `None` while searching.
+    pub total: Option<usize>,
+    pub loaded: usize,
+    pub failed: usize,
+}
+
 #[derive(TypePath)]
 struct ManifestLoader<M: AssetManifest> {
     registry: TypeRegistryArc,
@@ -92,17 +256,55 @@ impl<M: AssetManifest> AssetLoader for ManifestLoader<M> {
         _settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
-        let mut string = String::new();
-        reader.read_to_string(&mut string).await?;
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
 
-        let registry = self.registry.read();
-        let registration = registry.get(TypeId::of::<M>()).unwrap();
+        // Collect the manifest and all its parents, starting from the manifest itself.
+        let mut path = load_context.path().clone_owned();
+        let mut layers = Vec::new();
+        loop {
+            let (layer, extends) = {
+                // Scoped because the guard can't be held across await.
+                let registry = self.registry.read();
+                let layer = deserialize_layer::<M>(&bytes, &registry)
+                    .map_err(|e| format!("unable to parse '{path}': {e}"))?;
+                let extends = extends(&*layer).map(|extends| path.resolve_embed(&extends));
+                (layer, extends)
+            };
+            layers.push((path, layer));
 
-        let mut deserializer = ron::Deserializer::from_str(&string)?;
-        let reflect_deserializer = TypedReflectDeserializer::new(registration, &registry);
-        let reflect = reflect_deserializer.deserialize(&mut deserializer)?;
-        let mut manifest = M::take_from_reflect(reflect).unwrap();
-        manifest.resolve_paths(&registry, load_context.path());
+            let Some(parent) = extends else {
+                break;
+            };
+            if layers.iter().any(|(path, _)| *path == parent) {
+                let chain: Vec<_> = layers.iter().map(|(path, _)| format!("'{path}'")).collect();
+                return Err(
+                    format!("circular inheritance: {} -> '{parent}'", chain.join(" -> ")).into(),
+                );
+            }
+
+            bytes = load_context.read_asset_bytes(parent.clone()).await?;
+            path = parent;
+        }
+
+        let (manifest, referenced) = {
+            let registry = self.registry.read();
+            let manifest = merge_layers::<M>(&layers, &registry)?;
+            let referenced: Vec<_> = manifest
+                .components()
+                .iter()
+                .flat_map(|component| component.asset_paths(&registry))
+                .map(AssetPath::clone_owned)
+                .collect();
+            (manifest, referenced)
+        };
+        manifest.validate()?;
+
+        for path in referenced {
+            if !asset_exists(load_context, &path).await {
+                return Err(format!("referenced asset '{path}' doesn't exist").into());
+            }
+        }
 
         debug!("loaded {:?}", load_context.path());
 
@@ -114,6 +316,224 @@ impl<M: AssetManifest> AssetLoader for ManifestLoader<M> {
     }
 }
 
+/// Returns `true` if the asset exists in its source.
+///
+/// Only opens the asset without reading it since referenced assets can be large.
+async fn asset_exists(load_context: &LoadContext<'_>, path: &AssetPath<'_>) -> bool {
+    let Ok(source) = load_context
+        .asset_server()
+        .get_source(path.source().clone())
+    else {
+        return false;
+    };
+
+    source.reader().read(path.path()).await.is_ok()
+}
+
+/// Deserializes a manifest file that may omit fields.
+fn deserialize_layer<M: AssetManifest>(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+) -> Result<Box<dyn PartialReflect>> {
+    let registration = registry.get(TypeId::of::<M>()).unwrap();
+    let mut deserializer = ron::Deserializer::from_bytes(bytes)?;
+    let reflect_deserializer = TypedReflectDeserializer::new(registration, registry);
+    let layer = reflect_deserializer.deserialize(&mut deserializer)?;
+
+    Ok(layer)
+}
+
+/// Returns the unresolved path from the [`EXTENDS`] field.
+fn extends(layer: &dyn PartialReflect) -> Option<AssetPath<'static>> {
+    let ReflectRef::Struct(layer) = layer.reflect_ref() else {
+        return None;
+    };
+    let extends = layer.field(EXTENDS)?;
+    Option::<AssetPath<'static>>::from_reflect(extends).flatten()
+}
+
+/// Combines a manifest with its parents into a single manifest.
+///
+/// Layers go from the manifest itself to its most distant parent.
+/// Paths in each layer are resolved relative to the file it was loaded from.
+fn merge_layers<M: AssetManifest>(
+    layers: &[(AssetPath<'static>, Box<dyn PartialReflect>)],
+    registry: &TypeRegistry,
+) -> Result<M> {
+    let structs: Vec<_> = layers
+        .iter()
+        .filter_map(|(_, layer)| match layer.reflect_ref() {
+            ReflectRef::Struct(layer) => Some(layer),
+            _ => None,
+        })
+        .collect();
+    let mut manifest = M::default();
+    let info = registry.get(TypeId::of::<M>()).unwrap().type_info();
+    if let (TypeInfo::Struct(info), ReflectRef::Struct(defaults)) = (info, manifest.reflect_ref()) {
+        let mut missing = Vec::new();
+        missing_fields(registry, info, defaults, &structs, "", &mut missing);
+        if !missing.is_empty() {
+            return Err(format!("missing fields: {}", missing.join(", ")).into());
+        }
+    }
+
+    for (path, layer) in layers.iter().rev() {
+        // Resolve each layer separately since resolving
+        // already resolved paths would apply the base path twice.
+        let mut resolved = M::default();
+        overlay(resolved.as_partial_reflect_mut(), &**layer, registry)?;
+        resolved.resolve_paths(registry, path);
+        copy_fields(
+            manifest.as_partial_reflect_mut(),
+            resolved.as_partial_reflect(),
+            &**layer,
+        )?;
+    }
+
+    Ok(manifest)
+}
+
+/// Collects required fields that none of the layers specify.
+///
+/// Nested structs are merged, so their fields are checked individually.
+/// `defaults` is the default value of the struct described by `info`.
+fn missing_fields(
+    registry: &TypeRegistry,
+    info: &StructInfo,
+    defaults: &dyn Struct,
+    layers: &[&dyn Struct],
+    prefix: &str,
+    missing: &mut Vec<String>,
+) {
+    for field in info.iter() {
+        if field.name() == EXTENDS || is_optional(registry, Some(defaults), field) {
+            continue;
+        }
+
+        let name = if prefix.is_empty() {
+            field.name().to_string()
+        } else {
+            format!("{prefix}.{}", field.name())
+        };
+
+        let values: Vec<_> = layers
+            .iter()
+            .filter_map(|layer| layer.field(field.name()))
+            .collect();
+        if values.is_empty() {
+            missing.push(name);
+        } else if let Some(TypeInfo::Struct(info)) = registry.get_type_info(field.type_id())
+            && let Some(ReflectRef::Struct(defaults)) = defaults
+                .field(field.name())
+                .map(PartialReflect::reflect_ref)
+        {
+            let nested: Vec<_> = values
+                .iter()
+                .filter_map(|value| match value.reflect_ref() {
+                    ReflectRef::Struct(value) => Some(value),
+                    _ => None,
+                })
+                .collect();
+            missing_fields(registry, info, defaults, &nested, &name, missing);
+        }
+    }
+}
+
+/// Returns `true` if the field can be omitted in manifests.
+///
+/// This is the case for [`Option`] fields and fields marked with `#[reflect(default)]`.
+/// The latter can be detected only if `defaults` for the struct are available.
+fn is_optional(registry: &TypeRegistry, defaults: Option<&dyn Struct>, field: &NamedField) -> bool {
+    field.type_path().starts_with("core::option::Option<")
+        || defaults.is_some_and(|defaults| has_default(registry, defaults, field.name()))
+}
+
+/// Returns `true` if the struct can be constructed without the field.
+///
+/// This is the case for fields marked with `#[reflect(default)]`.
+fn has_default(registry: &TypeRegistry, defaults: &dyn Struct, field: &str) -> bool {
+    let Some(from_reflect) = defaults
+        .get_represented_type_info()
+        .and_then(|info| registry.get_type_data::<ReflectFromReflect>(info.type_id()))
+    else {
+        return false;
+    };
+
+    let mut probe = DynamicStruct::default();
+    probe.set_represented_type(defaults.get_represented_type_info());
+    for (index, value) in defaults.iter_fields().enumerate() {
+        let name = defaults.name_at(index).unwrap();
+        if name != field {
+            probe.insert_boxed(name, value.to_dynamic());
+        }
+    }
+
+    from_reflect.from_reflect(&probe).is_some()
+}
+
+/// Applies fields present in `overrides` to `target`.
+///
+/// Structs are merged field by field, all other values are replaced.
+fn overlay(
+    target: &mut dyn PartialReflect,
+    overrides: &dyn PartialReflect,
+    registry: &TypeRegistry,
+) -> Result<()> {
+    if let (ReflectMut::Struct(target), ReflectRef::Struct(overrides)) =
+        (target.reflect_mut(), overrides.reflect_ref())
+    {
+        for (index, value) in overrides.iter_fields().enumerate() {
+            let name = overrides.name_at(index).unwrap();
+            if let Some(field) = target.field_mut(name) {
+                overlay(field, value, registry)?;
+            }
+        }
+        return Ok(());
+    }
+
+    // Convert into a concrete value to replace lists entirely instead of patching elements.
+    let from_reflect = target
+        .get_represented_type_info()
+        .and_then(|info| registry.get_type_data::<ReflectFromReflect>(info.type_id()));
+    match from_reflect.and_then(|from_reflect| from_reflect.from_reflect(overrides)) {
+        Some(value) => target.try_apply(value.as_partial_reflect())?,
+        None => target.try_apply(overrides)?,
+    }
+
+    Ok(())
+}
+
+/// Copies fields from `source` to `target` that are present in `mask`.
+///
+/// Like [`overlay`], but takes values from an already converted `source`.
+fn copy_fields(
+    target: &mut dyn PartialReflect,
+    source: &dyn PartialReflect,
+    mask: &dyn PartialReflect,
+) -> Result<()> {
+    if let (ReflectMut::Struct(target), ReflectRef::Struct(source), ReflectRef::Struct(mask)) = (
+        target.reflect_mut(),
+        source.reflect_ref(),
+        mask.reflect_ref(),
+    ) {
+        for index in 0..mask.field_len() {
+            let name = mask.name_at(index).unwrap();
+            if let (Some(target), Some(source), Some(mask)) = (
+                target.field_mut(name),
+                source.field(name),
+                mask.field_at(index),
+            ) {
+                copy_fields(target, source, mask)?;
+            }
+        }
+        return Ok(());
+    }
+
+    target.try_apply(source)?;
+
+    Ok(())
+}
+
 impl<M: AssetManifest> FromWorld for ManifestLoader<M> {
     fn from_world(world: &mut World) -> Self {
         Self {
@@ -123,13 +543,36 @@ impl<M: AssetManifest> FromWorld for ManifestLoader<M> {
     }
 }
 
-trait AssetManifest: Asset + FromReflect {
+/// Manifest loaded from a RON file.
+///
+/// Manifests can inherit fields from another manifest or a template by specifying
+/// its path relative to the file in the [`EXTENDS`] field. Nested structs are merged
+/// field by field, all other fields, including lists, are replaced. Templates
+/// are files with [`TEMPLATE_EXTENSION`] that may omit fields and aren't loaded
+/// on their own.
+trait AssetManifest: Asset + FromReflect + Default {
     const EXTENSION: &'static str;
 
     fn resolve_paths(&mut self, registry: &TypeRegistry, manifest_path: &AssetPath);
+
+    /// Returns components that will be inserted from the manifest.
+    ///
+    /// Assets referenced by them are checked for existence on load.
+    fn components(&self) -> &[ReflectedComponent] {
+        &[]
+    }
+
+    /// Checks values that can't be expressed in types.
+    fn validate(&self) -> Result<()>;
 }
 
-#[derive(Deserialize, Reflect)]
+/// Field that every manifest declares for inheritance.
+const EXTENDS: &str = "extends";
+
+/// Extension of partial manifests that are only used as parents.
+const TEMPLATE_EXTENSION: &str = "template.ron";
+
+#[derive(Deserialize, Reflect, Default)]
 pub struct ManifestInfo {
     pub name: Name,
     pub author: String,
@@ -138,7 +581,197 @@ pub struct ManifestInfo {
 
 /// Maps paths inside reflected components.
 #[reflect_trait]
-trait ResolvePaths {
+pub(crate) trait ResolvePaths {
     /// Converts all relative paths into absolute paths.
     fn resolve_paths(&mut self, manifest_path: &AssetPath);
+
+    /// Returns all paths to assets referenced by the component.
+    fn asset_paths(&self) -> Vec<&AssetPath<'static>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bevy::asset::io::{
+        AssetSource,
+        memory::{Dir, MemoryAssetReader},
+    };
+    use test_log::test;
+
+    use super::*;
+    use crate::world::attachment::AttachedAsset;
+
+    #[test]
+    fn inheritance() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<ObjectManifest>();
+
+        let template = deserialize_layer::<ObjectManifest>(
+            br#"(
+                info: (author: "Author", license: "CC0"),
+                asset: "chair.glb",
+                variants: [],
+                categories: ["furniture"],
+                price: 100,
+                depreciation: 0.2,
+                description: "",
+                tags: ["chair"],
+                dimensions: (1.0, 1.0, 1.0),
+                components: [],
+            )"#,
+            &registry,
+        )
+        .unwrap();
+        let child = deserialize_layer::<ObjectManifest>(
+            br#"(
+                extends: Some("templates/chair.template.ron"),
+                info: (name: "Red chair"),
+                price: 150,
+            )"#,
+            &registry,
+        )
+        .unwrap();
+        assert_eq!(
+            extends(&*child),
+            Some(AssetPath::from("templates/chair.template.ron"))
+        );
+
+        let layers = [
+            (AssetPath::from("base/objects/red_chair.object.ron"), child),
+            (
+                AssetPath::from("base/objects/templates/chair.template.ron"),
+                template,
+            ),
+        ];
+        let manifest = merge_layers::<ObjectManifest>(&layers, &registry).unwrap();
+        assert_eq!(manifest.info.name.as_str(), "Red chair");
+        assert_eq!(manifest.info.author, "Author");
+        assert_eq!(manifest.price, 150);
+        assert_eq!(manifest.tags, ["chair"]);
+        assert_eq!(
+            manifest.asset,
+            AssetPath::from("base/objects/templates/chair.glb"),
+            "paths should be relative to the file that declares them"
+        );
+        assert_eq!(
+            manifest.extends,
+            Some(AssetPath::from("base/objects/templates/chair.template.ron"))
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<ObjectManifest>();
+
+        let layer = deserialize_layer::<ObjectManifest>(
+            br#"(
+                info: (name: "Chair"),
+                asset: "chair.glb",
+            )"#,
+            &registry,
+        )
+        .unwrap();
+
+        let error = merge_layers::<ObjectManifest>(
+            &[(AssetPath::from("chair.object.ron"), layer)],
+            &registry,
+        )
+        .err()
+        .unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("missing fields: info.author, info.license, variants"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn manifest_paths() {
+        let paths = ManifestPaths(
+            [(
+                PathBuf::from("objects/chair.object.ron"),
+                AssetSourceId::Name("my_mod".into()),
+            )]
+            .into(),
+        );
+
+        let canonical =
+            ManifestPaths::canonical(&AssetPath::from("my_mod://objects/chair.object.ron"));
+        assert_eq!(canonical, AssetPath::from("objects/chair.object.ron"));
+        assert_eq!(
+            paths.resolve(&canonical),
+            AssetPath::from("my_mod://objects/chair.object.ron"),
+            "overridden manifests should resolve to the mod"
+        );
+
+        let unknown = AssetPath::from("objects/table.object.ron");
+        assert_eq!(paths.resolve(&unknown), unknown);
+    }
+
+    #[test]
+    fn defaults() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<ObjectManifest>();
+
+        let layer = deserialize_layer::<ObjectManifest>(
+            br#"(
+                info: (name: "Chair", author: "Author", license: "CC0"),
+                asset: "chair.glb",
+                variants: [],
+                categories: ["furniture"],
+                components: [],
+            )"#,
+            &registry,
+        )
+        .unwrap();
+
+        let manifest = merge_layers::<ObjectManifest>(
+            &[(AssetPath::from("chair.object.ron"), layer)],
+            &registry,
+        )
+        .unwrap();
+        assert_eq!(manifest.price, 0);
+        assert_eq!(manifest.dimensions, Vec3::ZERO);
+        assert!(manifest.tags.is_empty());
+        manifest.validate().unwrap();
+    }
+
+    #[test]
+    fn missing_asset() {
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("chair.object.ron"),
+            r#"(
+                info: (name: "Chair", author: "Author", license: "CC0"),
+                asset: "chair.glb",
+                variants: [],
+                categories: [],
+                components: [{ "AttachedAsset": (asset: "missing.glb") }],
+            )"#,
+        );
+
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .register_type::<AttachedAsset>()
+        .init_asset::<ObjectManifest>()
+        .init_asset_loader::<ManifestLoader<ObjectManifest>>();
+
+        let asset_server = app.world().resource::<AssetServer>();
+        let error = block_on(asset_server.load_untyped_async("chair.object.ron"))
+            .expect_err("manifest with missing assets shouldn't load");
+        assert!(
+            error
+                .to_string()
+                .contains("referenced asset 'missing.glb' doesn't exist"),
+            "unexpected error: {error}"
+        );
+    }
 }
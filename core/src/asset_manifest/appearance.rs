@@ -0,0 +1,90 @@
+use bevy::{asset::AssetPath, prelude::*, reflect::TypeRegistry};
+
+use super::{AssetManifest, ManifestInfo, reflected_component::ReflectedComponent};
+use crate::world::character::Sex;
+
+/// Body, hair or outfit of a character.
+#[derive(Asset, Reflect, Default)]
+pub struct AppearanceManifest {
+    /// Manifest to inherit fields from, see [`AssetManifest`] for details.
+    pub extends: Option<AssetPath<'static>>,
+    pub info: ManifestInfo,
+    pub slot: AppearanceSlot,
+
+    /// Sexes that can use this appearance.
+    pub sexes: Vec<Sex>,
+    pub asset: AssetPath<'static>,
+
+    /// Inserted into the spawned part, not the character itself.
+    pub components: Vec<ReflectedComponent>,
+}
+
+impl AppearanceManifest {
+    /// Returns `true` if the manifest can be used in the slot for the sex.
+    pub fn fits(&self, slot: AppearanceSlot, sex: Sex) -> bool {
+        self.slot == slot && self.sexes.contains(&sex)
+    }
+}
+
+impl AssetManifest for AppearanceManifest {
+    const EXTENSION: &'static str = "appearance.ron";
+
+    fn resolve_paths(&mut self, registry: &TypeRegistry, manifest_path: &AssetPath) {
+        if let Some(extends) = &mut self.extends {
+            *extends = manifest_path.resolve_embed(extends);
+        }
+        self.asset = manifest_path.resolve_embed(&self.asset);
+        for component in &mut self.components {
+            component.resolve_paths(registry, manifest_path);
+        }
+    }
+
+    fn components(&self) -> &[ReflectedComponent] {
+        &self.components
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.sexes.is_empty() {
+            return Err("at least one sex should be specified".into());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Reflect, Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AppearanceSlot {
+    #[default]
+    Body,
+    Hair,
+    Outfit,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn fits() {
+        let manifest = AppearanceManifest {
+            slot: AppearanceSlot::Hair,
+            sexes: vec![Sex::Female],
+            ..Default::default()
+        };
+
+        assert!(manifest.fits(AppearanceSlot::Hair, Sex::Female));
+        assert!(!manifest.fits(AppearanceSlot::Hair, Sex::Male));
+        assert!(!manifest.fits(AppearanceSlot::Body, Sex::Female));
+    }
+
+    #[test]
+    fn validate() {
+        let mut manifest = AppearanceManifest::default();
+        assert!(manifest.validate().is_err(), "sexes shouldn't be empty");
+
+        manifest.sexes.push(Sex::Male);
+        assert!(manifest.validate().is_ok());
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::{asset::AssetPath, prelude::*, reflect::TypeRegistry};
+
+use super::AssetManifest;
+
+/// Node of the object category tree.
+///
+/// Categories reference their parents by ID, so mods can
+/// add subcategories to existing categories.
+#[derive(Asset, Reflect, Default)]
+pub struct CategoryManifest {
+    /// Manifest to inherit fields from, see [`AssetManifest`] for details.
+    pub extends: Option<AssetPath<'static>>,
+
+    /// Unique identifier referenced by objects and subcategories.
+    pub id: String,
+    pub name: String,
+
+    /// Button icon, the name is displayed if not set.
+    pub icon: Option<AssetPath<'static>>,
+    pub parent: Option<String>,
+}
+
+impl AssetManifest for CategoryManifest {
+    const EXTENSION: &'static str = "category.ron";
+
+    fn resolve_paths(&mut self, _registry: &TypeRegistry, manifest_path: &AssetPath) {
+        if let Some(extends) = &mut self.extends {
+            *extends = manifest_path.resolve_embed(extends);
+        }
+        if let Some(icon) = &mut self.icon {
+            *icon = manifest_path.resolve_embed(icon);
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.id.trim().is_empty() {
+            return Err("ID shouldn't be empty".into());
+        }
+
+        if self.parent.as_ref() == Some(&self.id) {
+            return Err(format!("'{}' can't be its own parent", self.id).into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Navigation over the category tree.
+pub trait CategoriesExt {
+    fn find(&self, id: &str) -> Option<&CategoryManifest>;
+
+    /// Returns categories with the specified parent sorted by name.
+    ///
+    /// Returns root categories if the parent is [`None`].
+    fn children(&self, parent: Option<&str>) -> Vec<&CategoryManifest>;
+
+    /// Returns `true` if the category is `ancestor` or one of its descendants.
+    fn is_within(&self, id: &str, ancestor: &str) -> bool;
+}
+
+impl CategoriesExt for Assets<CategoryManifest> {
+    fn find(&self, id: &str) -> Option<&CategoryManifest> {
+        self.iter()
+            .map(|(_, category)| category)
+            .find(|category| category.id == id)
+    }
+
+    fn children(&self, parent: Option<&str>) -> Vec<&CategoryManifest> {
+        let mut children: Vec<_> = self
+            .iter()
+            .map(|(_, category)| category)
+            .filter(|category| category.parent.as_deref() == parent)
+            .collect();
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        children
+    }
+
+    fn is_within(&self, id: &str, ancestor: &str) -> bool {
+        let mut current = Some(id);
+        // Limit the depth in case of cycles.
+        for _ in 0..=self.len() {
+            let Some(id) = current else {
+                return false;
+            };
+            if id == ancestor {
+                return true;
+            }
+            current = self
+                .find(id)
+                .and_then(|category| category.parent.as_deref());
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn tree() {
+        let mut categories = Assets::<CategoryManifest>::default();
+        for (id, parent) in [
+            ("room", None),
+            ("bedroom", Some("room")),
+            ("function", None),
+            ("seating", Some("function")),
+            ("cycle_a", Some("cycle_b")),
+            ("cycle_b", Some("cycle_a")),
+        ] {
+            categories.add(CategoryManifest {
+                extends: None,
+                id: id.to_string(),
+                name: id.to_string(),
+                icon: None,
+                parent: parent.map(str::to_string),
+            });
+        }
+
+        let roots: Vec<_> = categories
+            .children(None)
+            .into_iter()
+            .map(|category| category.id.as_str())
+            .collect();
+        assert_eq!(roots, ["function", "room"]);
+
+        assert!(categories.is_within("bedroom", "room"));
+        assert!(categories.is_within("room", "room"));
+        assert!(!categories.is_within("bedroom", "function"));
+        assert!(!categories.is_within("room", "bedroom"));
+        assert!(!categories.is_within("cycle_a", "room"));
+    }
+}
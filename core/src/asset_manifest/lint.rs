@@ -0,0 +1,303 @@
+use std::{iter, path::Component};
+
+use bevy::{
+    asset::{AssetPath, LoadState, LoadedUntypedAsset},
+    platform::collections::HashMap,
+    prelude::*,
+    reflect::TypeRegistry,
+};
+
+use super::{
+    AssetManifests,
+    appearance::AppearanceManifest,
+    category::{CategoriesExt, CategoryManifest},
+    object::ObjectManifest,
+    reflected_component::ReflectedComponent,
+    surface::SurfaceManifest,
+};
+use crate::mods::Mods;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(lint);
+}
+
+fn lint(
+    _on: On<LintManifests>,
+    mut exit: MessageWriter<AppExit>,
+    asset_server: Res<AssetServer>,
+    registry: Res<AppTypeRegistry>,
+    mods: Res<Mods>,
+    handles: Res<AssetManifests>,
+    untyped_manifests: Res<Assets<LoadedUntypedAsset>>,
+    objects: Res<Assets<ObjectManifest>>,
+    categories: Res<Assets<CategoryManifest>>,
+    appearances: Res<Assets<AppearanceManifest>>,
+    surfaces: Res<Assets<SurfaceManifest>>,
+) {
+    info!("checking {} manifests", handles.len());
+
+    let mut errors = Vec::new();
+    let mut manifests = Vec::new();
+    let mut category_manifests = Vec::new();
+    let mut appearance_manifests = Vec::new();
+    let mut surface_manifests = Vec::new();
+    for handle in handles.iter() {
+        let Some(path) = asset_server.get_path(handle) else {
+            continue;
+        };
+
+        match asset_server.load_state(handle) {
+            LoadState::Failed(e) => errors.push(e.to_string()),
+            LoadState::Loaded => {
+                let Some(untyped) = untyped_manifests.get(handle) else {
+                    continue;
+                };
+                if let Ok(handle) = untyped.handle.clone().try_typed::<ObjectManifest>()
+                    && let Some(manifest) = objects.get(&handle)
+                {
+                    manifests.push((path, manifest));
+                } else if let Ok(handle) = untyped.handle.clone().try_typed::<CategoryManifest>()
+                    && let Some(manifest) = categories.get(&handle)
+                {
+                    category_manifests.push((path, manifest));
+                } else if let Ok(handle) = untyped.handle.clone().try_typed::<AppearanceManifest>()
+                    && let Some(manifest) = appearances.get(&handle)
+                {
+                    appearance_manifests.push((path, manifest));
+                } else if let Ok(handle) = untyped.handle.clone().try_typed::<SurfaceManifest>()
+                    && let Some(manifest) = surfaces.get(&handle)
+                {
+                    surface_manifests.push((path, manifest));
+                }
+            }
+            LoadState::NotLoaded | LoadState::Loading => {
+                errors.push(format!("'{path}' wasn't loaded"));
+            }
+        }
+    }
+    manifests.sort_by_key(|(path, _)| path.to_string());
+    category_manifests.sort_by_key(|(path, _)| path.to_string());
+    appearance_manifests.sort_by_key(|(path, _)| path.to_string());
+    surface_manifests.sort_by_key(|(path, _)| path.to_string());
+
+    let exists = |path: &AssetPath| {
+        mods.source_dir(path.source())
+            .is_some_and(|dir| dir.join(path.path()).exists())
+    };
+    let registry = registry.read();
+    errors.extend(check_categories(&category_manifests, &categories));
+    errors.extend(check_objects(&manifests, &registry, &categories, exists));
+    errors.extend(check_appearances(&appearance_manifests, &registry, exists));
+    errors.extend(check_surfaces(&surface_manifests, exists));
+
+    if errors.is_empty() {
+        info!("no errors found");
+        exit.write(AppExit::Success);
+    } else {
+        for error in &errors {
+            error!("{error}");
+        }
+        error!("found {} errors", errors.len());
+        exit.write(AppExit::error());
+    }
+}
+
+/// Returns broken references in the category tree.
+fn check_categories(
+    manifests: &[(AssetPath, &CategoryManifest)],
+    categories: &Assets<CategoryManifest>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut ids = HashMap::<_, &AssetPath>::new();
+    for (path, manifest) in manifests {
+        if let Some(parent) = &manifest.parent {
+            if categories.find(parent).is_none() {
+                errors.push(format!("'{path}': parent '{parent}' doesn't exist"));
+            } else if categories.is_within(parent, &manifest.id) {
+                errors.push(format!(
+                    "'{path}': parent '{parent}' is a descendant of '{}'",
+                    manifest.id
+                ));
+            }
+        }
+
+        if let Some(other) = ids.insert(manifest.id.as_str(), path) {
+            errors.push(format!(
+                "'{path}': ID '{}' is already used by '{other}'",
+                manifest.id
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Returns errors that don't prevent manifests from loading,
+/// but would break the game at runtime.
+fn check_objects(
+    manifests: &[(AssetPath, &ObjectManifest)],
+    registry: &TypeRegistry,
+    categories: &Assets<CategoryManifest>,
+    exists: impl Fn(&AssetPath) -> bool,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut names = HashMap::<_, &AssetPath>::new();
+    for (path, manifest) in manifests {
+        let variant_assets = manifest
+            .variants
+            .iter()
+            .flat_map(|variant| [&variant.asset, &variant.material])
+            .flatten();
+        for asset in iter::once(&manifest.asset).chain(variant_assets) {
+            check_asset(&mut errors, path, asset, &exists);
+        }
+
+        for category in &manifest.categories {
+            if categories.find(category).is_none() {
+                errors.push(format!("'{path}': category '{category}' doesn't exist"));
+            }
+        }
+
+        check_components(&mut errors, path, &manifest.components, registry, &exists);
+
+        if let Some(other) = names.insert(manifest.info.name.as_str(), path) {
+            errors.push(format!(
+                "'{path}': name '{}' is already used by '{other}'",
+                manifest.info.name
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Like [`check_objects`], but for character appearances.
+fn check_appearances(
+    manifests: &[(AssetPath, &AppearanceManifest)],
+    registry: &TypeRegistry,
+    exists: impl Fn(&AssetPath) -> bool,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (path, manifest) in manifests {
+        check_asset(&mut errors, path, &manifest.asset, &exists);
+        check_components(&mut errors, path, &manifest.components, registry, &exists);
+    }
+
+    errors
+}
+
+/// Like [`check_objects`], but for wall and floor coverings.
+fn check_surfaces(
+    manifests: &[(AssetPath, &SurfaceManifest)],
+    exists: impl Fn(&AssetPath) -> bool,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (path, manifest) in manifests {
+        for texture in manifest.textures.iter() {
+            check_asset(&mut errors, path, texture, &exists);
+        }
+    }
+
+    errors
+}
+
+fn check_asset(
+    errors: &mut Vec<String>,
+    path: &AssetPath,
+    asset: &AssetPath,
+    exists: impl Fn(&AssetPath) -> bool,
+) {
+    if asset.path().components().any(|c| c == Component::ParentDir) {
+        errors.push(format!(
+            "'{path}': asset '{asset}' points outside of its source"
+        ));
+    } else if !exists(asset) {
+        errors.push(format!("'{path}': asset '{asset}' doesn't exist"));
+    }
+}
+
+fn check_components(
+    errors: &mut Vec<String>,
+    path: &AssetPath,
+    components: &[ReflectedComponent],
+    registry: &TypeRegistry,
+    exists: impl Fn(&AssetPath) -> bool,
+) {
+    for component in components {
+        for asset in component.asset_paths(registry) {
+            check_asset(errors, path, asset, &exists);
+        }
+
+        let type_path = component.type_path();
+        if registry
+            .get_with_type_path(type_path)
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .is_none()
+        {
+            errors.push(format!(
+                "'{path}': `{type_path}` is not registered as a component"
+            ));
+        }
+    }
+}
+
+/// Checks all loaded manifests and exits.
+///
+/// The app exits with an error if any of them are invalid.
+#[derive(Event)]
+pub struct LintManifests;
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+    use crate::asset_manifest::object::ObjectVariant;
+
+    #[test]
+    fn objects() {
+        let manifests = [
+            ("a.object.ron", "A", "a.glb", "furniture"),
+            ("b.object.ron", "A", "missing.glb", "furniture"),
+            ("c.object.ron", "C", "../outside.glb", "missing"),
+        ]
+        .map(|(path, name, asset, category)| {
+            let mut manifest = ObjectManifest {
+                asset: asset.into(),
+                variants: vec![ObjectVariant {
+                    name: "Red".to_string(),
+                    material: Some("a.glb#Material0".into()),
+                    ..Default::default()
+                }],
+                categories: vec![category.to_string()],
+                ..Default::default()
+            };
+            manifest.info.name = name.into();
+            (AssetPath::from(path), manifest)
+        });
+        let manifests: Vec<_> = manifests
+            .iter()
+            .map(|(path, manifest)| (path.clone(), manifest))
+            .collect();
+
+        let mut categories = Assets::default();
+        categories.add(CategoryManifest {
+            id: "furniture".to_string(),
+            name: "Furniture".to_string(),
+            ..Default::default()
+        });
+
+        let errors = check_objects(&manifests, &TypeRegistry::default(), &categories, |path| {
+            path.path().ends_with("a.glb")
+        });
+        assert_eq!(
+            errors,
+            [
+                "'b.object.ron': asset 'missing.glb' doesn't exist",
+                "'b.object.ron': name 'A' is already used by 'a.object.ron'",
+                "'c.object.ron': asset '../outside.glb' points outside of its source",
+                "'c.object.ron': category 'missing' doesn't exist",
+            ]
+        );
+    }
+}
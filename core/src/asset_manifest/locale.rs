@@ -0,0 +1,43 @@
+use bevy::{asset::AssetPath, platform::collections::HashMap, prelude::*, reflect::TypeRegistry};
+
+use super::AssetManifest;
+
+/// Translated strings for a single language.
+///
+/// Multiple files can provide strings for the same language,
+/// which allows mods to translate their own content.
+#[derive(Asset, Reflect, Default)]
+pub struct LocaleManifest {
+    /// Manifest to inherit fields from, see [`AssetManifest`] for details.
+    pub extends: Option<AssetPath<'static>>,
+
+    /// Language code, like `en` or `pt-BR`.
+    pub language: String,
+
+    /// Name of the language in itself, like `Deutsch`.
+    pub name: String,
+
+    /// Translations by keys.
+    ///
+    /// UI strings use dotted keys, like `main_menu.play`.
+    /// Manifest fields use their English text as keys.
+    pub strings: HashMap<String, String>,
+}
+
+impl AssetManifest for LocaleManifest {
+    const EXTENSION: &'static str = "locale.ron";
+
+    fn resolve_paths(&mut self, _registry: &TypeRegistry, manifest_path: &AssetPath) {
+        if let Some(extends) = &mut self.extends {
+            *extends = manifest_path.resolve_embed(extends);
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.language.trim().is_empty() {
+            return Err("language shouldn't be empty".into());
+        }
+
+        Ok(())
+    }
+}
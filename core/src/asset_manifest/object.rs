@@ -1,40 +1,174 @@
 use bevy::{asset::AssetPath, prelude::*, reflect::TypeRegistry};
-use serde::Deserialize;
 
-use super::{
-    AssetManifest, ManifestInfo, ReflectResolvePaths, reflected_component::ReflectedComponent,
-};
+use super::{AssetManifest, ManifestInfo, reflected_component::ReflectedComponent};
 
-#[derive(Asset, Reflect)]
+#[derive(Asset, Reflect, Default)]
+#[reflect(Default)]
 pub struct ObjectManifest {
+    /// Manifest to inherit fields from, see [`AssetManifest`] for details.
+    pub extends: Option<AssetPath<'static>>,
     pub info: ManifestInfo,
     pub asset: AssetPath<'static>,
-    pub category: ObjectCategory,
+
+    /// Alternative looks of the object, the first one is used by default.
+    ///
+    /// If empty, only [`Self::asset`] is used.
+    pub variants: Vec<ObjectVariant>,
+
+    /// IDs of [`CategoryManifest`](super::category::CategoryManifest)s the object belongs to.
+    pub categories: Vec<String>,
+    #[reflect(default)]
+    pub price: u32,
+
+    /// Fraction of the price lost when the object is sold, from 0 to 1.
+    #[reflect(default)]
+    pub depreciation: f32,
+    #[reflect(default)]
+    pub description: String,
+
+    /// Keywords for the catalog search.
+    #[reflect(default)]
+    pub tags: Vec<String>,
+
+    /// Size of the object in meters, zero if unknown.
+    #[reflect(default)]
+    pub dimensions: Vec3,
     pub components: Vec<ReflectedComponent>,
 }
 
+impl ObjectManifest {
+    /// Returns the amount refunded when the object is sold.
+    pub fn resale_price(&self) -> u32 {
+        (self.price as f32 * (1.0 - self.depreciation)).round() as u32
+    }
+
+    /// Returns `true` if the name is empty or matches one of the variants.
+    ///
+    /// Empty names select the first variant.
+    pub fn is_valid_variant(&self, name: &str) -> bool {
+        name.is_empty() || self.variants.iter().any(|variant| variant.name == name)
+    }
+
+    /// Returns the variant by name.
+    ///
+    /// Falls back to the first variant if the name is empty or doesn't exist.
+    pub fn find_variant(&self, name: &str) -> Option<&ObjectVariant> {
+        self.variants
+            .iter()
+            .find(|variant| variant.name == name)
+            .or_else(|| self.variants.first())
+    }
+
+    /// Returns the name of the variant that follows the given one, wrapping around.
+    ///
+    /// Returns [`None`] if there is nothing to switch to.
+    pub fn next_variant(&self, name: &str) -> Option<&str> {
+        if self.variants.len() < 2 {
+            return None;
+        }
+
+        let index = self
+            .variants
+            .iter()
+            .position(|variant| variant.name == name)
+            .unwrap_or_default();
+        let next = &self.variants[(index + 1) % self.variants.len()];
+        Some(&next.name)
+    }
+
+    /// Returns the asset for the variant name.
+    ///
+    /// Falls back to [`Self::asset`] if the variant doesn't override it.
+    pub fn variant_asset(&self, name: &str) -> &AssetPath<'static> {
+        self.find_variant(name)
+            .and_then(|variant| variant.asset.as_ref())
+            .unwrap_or(&self.asset)
+    }
+
+    /// Returns the material that replaces all materials of the asset for the variant name.
+    pub fn variant_material(&self, name: &str) -> Option<&AssetPath<'static>> {
+        self.find_variant(name)
+            .and_then(|variant| variant.material.as_ref())
+    }
+}
+
 impl AssetManifest for ObjectManifest {
     const EXTENSION: &'static str = "object.ron";
 
+    fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.depreciation) {
+            return Err(format!(
+                "depreciation should be between 0 and 1, but got {}",
+                self.depreciation
+            )
+            .into());
+        }
+
+        if self.dimensions != Vec3::ZERO
+            && (!self.dimensions.is_finite() || self.dimensions.cmple(Vec3::ZERO).any())
+        {
+            return Err(
+                format!("dimensions should be positive, but got {}", self.dimensions).into(),
+            );
+        }
+
+        if self.tags.iter().any(|tag| tag.trim().is_empty()) {
+            return Err("tags shouldn't be empty".into());
+        }
+
+        if self
+            .variants
+            .iter()
+            .any(|variant| variant.name.trim().is_empty())
+        {
+            return Err("variant names shouldn't be empty".into());
+        }
+
+        for (index, variant) in self.variants.iter().enumerate() {
+            if self.variants[..index]
+                .iter()
+                .any(|other| other.name == variant.name)
+            {
+                return Err(format!("variant name '{}' is used twice", variant.name).into());
+            }
+        }
+
+        Ok(())
+    }
+
     fn resolve_paths(&mut self, registry: &TypeRegistry, manifest_path: &AssetPath) {
+        if let Some(extends) = &mut self.extends {
+            *extends = manifest_path.resolve_embed(extends);
+        }
         self.asset = manifest_path.resolve_embed(&self.asset);
-        for component in &mut self.components {
-            let type_path = component.reflect_type_path();
-            let Some(registration) = registry.get_with_type_path(type_path) else {
-                continue;
-            };
-            if let Some(resolve_path) =
-                registry.get_type_data::<ReflectResolvePaths>(registration.type_id())
-                && let Some(component) = resolve_path.get_mut(component)
-            {
-                component.resolve_paths(manifest_path);
+        for variant in &mut self.variants {
+            if let Some(asset) = &mut variant.asset {
+                *asset = manifest_path.resolve_embed(asset);
+            }
+            if let Some(material) = &mut variant.material {
+                *material = manifest_path.resolve_embed(material);
             }
         }
+        for component in &mut self.components {
+            component.resolve_paths(registry, manifest_path);
+        }
+    }
+
+    fn components(&self) -> &[ReflectedComponent] {
+        &self.components
     }
 }
 
-#[derive(Deserialize, Reflect, PartialEq, Clone, Copy)]
-pub enum ObjectCategory {
-    Furniture,
-    Foliage,
+/// Color, material or mesh swap of an object.
+#[derive(Reflect, Default)]
+pub struct ObjectVariant {
+    pub name: String,
+
+    /// Replaces [`ObjectManifest::asset`].
+    #[reflect(default)]
+    pub asset: Option<AssetPath<'static>>,
+
+    /// Replaces all materials of the asset.
+    #[reflect(default)]
+    pub material: Option<AssetPath<'static>>,
 }
@@ -4,9 +4,10 @@ use std::{
 };
 
 use bevy::{
+    asset::AssetPath,
     prelude::*,
     reflect::{
-        TypeRegistry,
+        TypeData, TypeRegistry,
         serde::{
             DeserializeWithRegistry, ReflectDeserializeWithRegistry, TypedReflectDeserializer,
         },
@@ -17,6 +18,8 @@ use serde::{
     de::{self, MapAccess, Visitor},
 };
 
+use super::ReflectResolvePaths;
+
 /// Wrapper to implement deserialization for a reflected component.
 ///
 /// We use reflection for deserialization because we need access to the
@@ -29,6 +32,54 @@ use serde::{
 #[reflect(DeserializeWithRegistry)]
 pub struct ReflectedComponent(Arc<dyn PartialReflect>);
 
+impl ReflectedComponent {
+    /// Converts relative paths inside the component.
+    ///
+    /// Does nothing if the component doesn't implement [`ResolvePaths`](super::ResolvePaths).
+    pub(super) fn resolve_paths(&mut self, registry: &TypeRegistry, manifest_path: &AssetPath) {
+        let (Some(resolve_paths), Some(from_reflect)) = (
+            self.type_data::<ReflectResolvePaths>(registry),
+            self.type_data::<ReflectFromReflect>(registry),
+        ) else {
+            return;
+        };
+
+        // Modify a copy because the value is shared with the deserialized layer.
+        let Some(mut component) = from_reflect.from_reflect(&*self.0) else {
+            return;
+        };
+        resolve_paths
+            .get_mut(&mut *component)
+            .unwrap()
+            .resolve_paths(manifest_path);
+        self.0 = component.into_partial_reflect().into();
+    }
+
+    /// Returns assets referenced by the component.
+    ///
+    /// Empty if the component doesn't implement [`ResolvePaths`](super::ResolvePaths).
+    pub(super) fn asset_paths(&self, registry: &TypeRegistry) -> Vec<&AssetPath<'static>> {
+        self.type_data::<ReflectResolvePaths>(registry)
+            .zip(self.0.try_as_reflect())
+            .and_then(|(resolve_paths, component)| resolve_paths.get(component))
+            .map(|component| component.asset_paths())
+            .unwrap_or_default()
+    }
+
+    /// Returns type path of the wrapped component.
+    pub(super) fn type_path(&self) -> &str {
+        self.0
+            .get_represented_type_info()
+            .map(|info| info.type_path())
+            .unwrap_or_else(|| self.0.reflect_type_path())
+    }
+
+    fn type_data<'a, T: TypeData>(&self, registry: &'a TypeRegistry) -> Option<&'a T> {
+        let info = self.0.get_represented_type_info()?;
+        registry.get_type_data::<T>(info.type_id())
+    }
+}
+
 impl<'de> DeserializeWithRegistry<'de> for ReflectedComponent {
     fn deserialize<D: Deserializer<'de>>(
         deserializer: D,
@@ -92,6 +143,7 @@ mod tests {
     use serde::de::DeserializeSeed;
 
     use super::*;
+    use crate::asset_manifest::ResolvePaths;
 
     #[test]
     fn deserialize() {
@@ -121,6 +173,26 @@ mod tests {
         assert_eq!(format!("{value:?}"), format!("{expected:?}",));
     }
 
+    #[test]
+    fn resolve_paths() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<TestAsset>();
+
+        let mut component = ReflectedComponent(Arc::new(TestAsset("texture.png".into())));
+        let layer = component.0.clone();
+        component.resolve_paths(&registry, &"objects/chair.object.ron".into());
+
+        assert_eq!(component.type_path(), TestAsset::type_path());
+        assert_eq!(
+            component.asset_paths(&registry),
+            [&AssetPath::from("objects/texture.png")]
+        );
+
+        // The value shared with the layer should stay unchanged.
+        let layer = layer.try_downcast_ref::<TestAsset>().unwrap();
+        assert_eq!(layer.0, AssetPath::from("texture.png"));
+    }
+
     #[derive(Reflect, Debug)]
     struct TestStruct {
         list: Vec<ReflectedComponent>,
@@ -129,4 +201,18 @@ mod tests {
     #[derive(Component, Reflect, Default)]
     #[reflect(Component, Default)]
     struct TestComponent(usize);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component, ResolvePaths)]
+    struct TestAsset(AssetPath<'static>);
+
+    impl ResolvePaths for TestAsset {
+        fn resolve_paths(&mut self, manifest_path: &AssetPath) {
+            self.0 = manifest_path.resolve_embed(&self.0);
+        }
+
+        fn asset_paths(&self) -> Vec<&AssetPath<'static>> {
+            vec![&self.0]
+        }
+    }
 }
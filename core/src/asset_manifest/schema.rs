@@ -0,0 +1,411 @@
+use std::{any::TypeId, fs, path::PathBuf};
+
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    reflect::{NamedField, ReflectRef, Struct, TypeInfo, TypeRegistry, VariantInfo},
+};
+use serde_json::{Map, Value, json};
+
+use super::{object::ObjectManifest, reflected_component::ReflectedComponent};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(export);
+}
+
+fn export(
+    export: On<ExportSchema>,
+    mut exit: MessageWriter<AppExit>,
+    registry: Res<AppTypeRegistry>,
+) {
+    let schema = object_schema(&registry.read());
+    let content = serde_json::to_string_pretty(&schema).expect("schema should be serializable");
+
+    match &export.path {
+        Some(path) => {
+            if let Err(e) = fs::write(path, content) {
+                error!("unable to write schema to {path:?}: {e}");
+                exit.write(AppExit::error());
+                return;
+            }
+            info!("exported schema to {path:?}");
+        }
+        None => println!("{content}"),
+    }
+
+    exit.write(AppExit::Success);
+}
+
+/// Generates JSON Schema for [`ObjectManifest`] from the reflection data.
+///
+/// Components can be specified by their short names if they are unambiguous.
+/// Fields are optional if the manifest extends another one, they are [`Option`]s
+/// or marked with `#[reflect(default)]`.
+pub fn object_schema(registry: &TypeRegistry) -> Value {
+    let mut builder = SchemaBuilder {
+        registry,
+        defs: Default::default(),
+    };
+    let root = builder.reference(TypeId::of::<ObjectManifest>());
+    builder.allow_inheritance(ObjectManifest::type_path());
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Object manifest",
+        "$ref": root["$ref"],
+        "$defs": builder.defs,
+    })
+}
+
+struct SchemaBuilder<'a> {
+    registry: &'a TypeRegistry,
+
+    /// Definitions by type paths.
+    defs: Map<String, Value>,
+}
+
+impl SchemaBuilder<'_> {
+    /// Returns schema for primitives or a reference to the definition for other types.
+    fn reference(&mut self, type_id: TypeId) -> Value {
+        if let Some(schema) = primitive(type_id) {
+            return schema;
+        }
+
+        let registry = self.registry;
+        let Some(type_info) = registry.get_type_info(type_id) else {
+            return json!({});
+        };
+
+        let type_path = type_info.type_path();
+        if !self.defs.contains_key(type_path) {
+            // Insert a placeholder first to handle recursive types.
+            self.defs.insert(type_path.to_string(), Value::Null);
+            let schema = self.definition(type_info);
+            self.defs.insert(type_path.to_string(), schema);
+        }
+
+        json!({ "$ref": format!("#/$defs/{type_path}") })
+    }
+
+    fn definition(&mut self, type_info: &TypeInfo) -> Value {
+        if type_info.type_id() == TypeId::of::<ReflectedComponent>() {
+            return self.component();
+        }
+
+        let mut scalars = Vec::new();
+        if self.glam_scalars(type_info, &mut scalars) {
+            return self.tuple(scalars.into_iter());
+        }
+
+        match type_info {
+            TypeInfo::Struct(info) => {
+                let defaults = self
+                    .registry
+                    .get_type_data::<ReflectDefault>(info.type_id())
+                    .map(ReflectDefault::default);
+                let defaults = match defaults.as_deref().map(|defaults| defaults.reflect_ref()) {
+                    Some(ReflectRef::Struct(defaults)) => Some(defaults),
+                    _ => None,
+                };
+                self.object(info.iter(), defaults)
+            }
+            TypeInfo::TupleStruct(info) => self.tuple(info.iter().map(|field| field.type_id())),
+            TypeInfo::Tuple(info) => self.tuple(info.iter().map(|field| field.type_id())),
+            TypeInfo::List(info) => json!({
+                "type": "array",
+                "items": self.reference(info.item_ty().id()),
+            }),
+            TypeInfo::Array(info) => json!({
+                "type": "array",
+                "items": self.reference(info.item_ty().id()),
+                "minItems": info.capacity(),
+                "maxItems": info.capacity(),
+            }),
+            TypeInfo::Set(info) => json!({
+                "type": "array",
+                "items": self.reference(info.value_ty().id()),
+                "uniqueItems": true,
+            }),
+            TypeInfo::Map(info) => json!({
+                "type": "object",
+                "additionalProperties": self.reference(info.value_ty().id()),
+            }),
+            TypeInfo::Enum(info) => {
+                let variants: Vec<_> = info
+                    .iter()
+                    .map(|variant| match variant {
+                        VariantInfo::Unit(variant) => json!({ "const": variant.name() }),
+                        VariantInfo::Struct(variant) => {
+                            let fields = self.object(variant.iter(), None);
+                            single_key(variant.name(), fields)
+                        }
+                        VariantInfo::Tuple(variant) => {
+                            let fields = self.tuple(variant.iter().map(|field| field.type_id()));
+                            single_key(variant.name(), fields)
+                        }
+                    })
+                    .collect();
+
+                json!({ "oneOf": variants })
+            }
+            TypeInfo::Opaque(info) => json!({ "description": info.type_path() }),
+        }
+    }
+
+    /// Returns schema for a struct with named fields.
+    ///
+    /// `defaults` is the default value of the struct, if available.
+    fn object<'a>(
+        &mut self,
+        fields: impl Iterator<Item = &'a NamedField>,
+        defaults: Option<&dyn Struct>,
+    ) -> Value {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for field in fields {
+            properties.insert(field.name().to_string(), self.reference(field.type_id()));
+            if !super::is_optional(self.registry, defaults, field) {
+                required.push(field.name());
+            }
+        }
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    fn tuple(&mut self, fields: impl Iterator<Item = TypeId>) -> Value {
+        let items: Vec<_> = fields.map(|type_id| self.reference(type_id)).collect();
+
+        json!({
+            "type": "array",
+            "prefixItems": items,
+            "minItems": items.len(),
+            "maxItems": items.len(),
+        })
+    }
+
+    /// Collects scalar types of a glam type in the order of serialization.
+    ///
+    /// Glam types are reflected as structs, but serialized as flat sequences of their scalars.
+    /// Returns `false` if the type isn't from glam.
+    fn glam_scalars(&self, type_info: &TypeInfo, scalars: &mut Vec<TypeId>) -> bool {
+        let TypeInfo::Struct(info) = type_info else {
+            return false;
+        };
+        if type_info.type_path_table().crate_name() != Some("glam") {
+            return false;
+        }
+
+        for field in info.iter() {
+            if primitive(field.type_id()).is_some() {
+                scalars.push(field.type_id());
+            } else if !self
+                .registry
+                .get_type_info(field.type_id())
+                .is_some_and(|info| self.glam_scalars(info, scalars))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Requires all fields of the manifest only if it doesn't extend another one.
+    ///
+    /// Nested structs are merged field by field, so their fields are always optional.
+    fn allow_inheritance(&mut self, type_path: &str) {
+        let Some(Value::Object(manifest)) = self.defs.get_mut(type_path) else {
+            return;
+        };
+        let Some(Value::Array(required)) = manifest.remove("required") else {
+            return;
+        };
+
+        let required: Vec<_> = required
+            .into_iter()
+            .filter(|field| field != "extends")
+            .collect();
+        manifest.insert(
+            "anyOf".to_string(),
+            json!([{ "required": ["extends"] }, { "required": required }]),
+        );
+
+        let nested: Vec<_> = manifest["properties"]
+            .as_object()
+            .into_iter()
+            .flat_map(|properties| properties.values())
+            .filter_map(|property| property["$ref"].as_str()?.strip_prefix("#/$defs/"))
+            .map(str::to_string)
+            .collect();
+        for type_path in nested {
+            if let Some(Value::Object(def)) = self.defs.get_mut(&type_path)
+                && def.contains_key("properties")
+            {
+                def.remove("required");
+            }
+        }
+    }
+
+    /// Lists all components that can be specified in manifests.
+    ///
+    /// See [`ReflectedComponent`] for the format.
+    fn component(&mut self) -> Value {
+        let registry = self.registry;
+        let mut registrations: Vec<_> = registry
+            .iter()
+            .filter(|registration| registration.data::<ReflectComponent>().is_some())
+            .filter(|registration| {
+                let short_path = registration.type_info().type_path_table().short_path();
+                !registry.is_ambiguous(short_path)
+            })
+            .collect();
+        registrations.sort_by_key(|registration| registration.type_info().type_path());
+
+        let mut variants = Vec::new();
+        for registration in registrations {
+            let short_path = registration.type_info().type_path_table().short_path();
+            if registration.data::<ReflectDefault>().is_some() {
+                variants.push(json!({ "const": short_path }));
+            }
+            let schema = self.reference(registration.type_id());
+            variants.push(single_key(short_path, schema));
+        }
+
+        json!({ "oneOf": variants })
+    }
+}
+
+/// Returns schema for types that serialize into JSON primitives.
+fn primitive(type_id: TypeId) -> Option<Value> {
+    let schema = if type_id == TypeId::of::<bool>() {
+        json!({ "type": "boolean" })
+    } else if [
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<u128>(),
+        TypeId::of::<usize>(),
+    ]
+    .contains(&type_id)
+    {
+        json!({ "type": "integer", "minimum": 0 })
+    } else if [
+        TypeId::of::<i8>(),
+        TypeId::of::<i16>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+        TypeId::of::<i128>(),
+        TypeId::of::<isize>(),
+    ]
+    .contains(&type_id)
+    {
+        json!({ "type": "integer" })
+    } else if [TypeId::of::<f32>(), TypeId::of::<f64>()].contains(&type_id) {
+        json!({ "type": "number" })
+    } else if [
+        TypeId::of::<char>(),
+        TypeId::of::<String>(),
+        TypeId::of::<PathBuf>(),
+        TypeId::of::<Name>(),
+        TypeId::of::<AssetPath<'static>>(),
+    ]
+    .contains(&type_id)
+    {
+        json!({ "type": "string" })
+    } else {
+        return None;
+    };
+
+    Some(schema)
+}
+
+/// Returns schema for an object with a single required key.
+///
+/// Used for enum variants and components.
+fn single_key(key: &str, value: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": { key: value },
+        "required": [key],
+        "additionalProperties": false,
+    })
+}
+
+/// Exports JSON Schema for object manifests and exits.
+///
+/// Prints the schema to stdout if the path is not set.
+#[derive(Event)]
+pub struct ExportSchema {
+    pub path: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+    use crate::asset_manifest::{ManifestInfo, object::ObjectVariant};
+
+    #[test]
+    fn object() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<ObjectManifest>();
+        registry.register::<TestComponent>();
+
+        let schema = object_schema(&registry);
+        let defs = &schema["$defs"];
+
+        let manifest = &defs[ObjectManifest::type_path()];
+        assert_eq!(manifest["anyOf"][0], json!({ "required": ["extends"] }));
+        assert_eq!(
+            manifest["anyOf"][1]["required"],
+            json!(["info", "asset", "variants", "categories", "components"]),
+            "fields with defaults should be optional"
+        );
+        assert_eq!(manifest["properties"]["asset"], json!({ "type": "string" }));
+
+        let variant = &defs[ObjectVariant::type_path()];
+        assert_eq!(
+            variant["required"],
+            json!(["name"]),
+            "options should be optional"
+        );
+
+        let number = json!({ "type": "number" });
+        assert_eq!(
+            defs[Vec3::type_path()],
+            json!({
+                "type": "array",
+                "prefixItems": [number, number, number],
+                "minItems": 3,
+                "maxItems": 3,
+            }),
+            "glam types should be serialized as tuples"
+        );
+
+        let info = &defs[ManifestInfo::type_path()];
+        assert!(
+            info.get("required").is_none(),
+            "nested fields should be merged"
+        );
+
+        let components = defs[ReflectedComponent::type_path()]["oneOf"]
+            .as_array()
+            .unwrap();
+        assert!(components.contains(&json!({ "const": "TestComponent" })));
+        assert!(components.contains(&single_key(
+            "TestComponent",
+            json!({ "$ref": format!("#/$defs/{}", TestComponent::type_path()) })
+        )));
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, Default)]
+    struct TestComponent(usize);
+}
@@ -0,0 +1,89 @@
+use bevy::{asset::AssetPath, math::Affine2, prelude::*, reflect::TypeRegistry};
+
+use super::{AssetManifest, ManifestInfo};
+
+/// Paint, wallpaper or tiles that cover walls or floors.
+#[derive(Asset, Reflect, Default)]
+pub struct SurfaceManifest {
+    /// Manifest to inherit fields from, see [`AssetManifest`] for details.
+    pub extends: Option<AssetPath<'static>>,
+    pub info: ManifestInfo,
+    pub kind: SurfaceKind,
+
+    /// Price per square meter.
+    pub price: u32,
+    pub textures: SurfaceTextures,
+
+    /// Size in meters covered by a single repetition of the textures.
+    pub tile_size: Vec2,
+}
+
+impl SurfaceManifest {
+    /// Returns UV transform that repeats textures according to [`Self::tile_size`].
+    ///
+    /// Assumes that UVs of the surface mesh are in meters.
+    pub fn uv_transform(&self) -> Affine2 {
+        Affine2::from_scale(self.tile_size.recip())
+    }
+}
+
+impl AssetManifest for SurfaceManifest {
+    const EXTENSION: &'static str = "surface.ron";
+
+    fn resolve_paths(&mut self, _registry: &TypeRegistry, manifest_path: &AssetPath) {
+        if let Some(extends) = &mut self.extends {
+            *extends = manifest_path.resolve_embed(extends);
+        }
+        for texture in self.textures.iter_mut() {
+            *texture = manifest_path.resolve_embed(texture);
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.tile_size.is_finite() || self.tile_size.cmple(Vec2::ZERO).any() {
+            return Err(format!("tile size should be positive, but got {}", self.tile_size).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Reflect, Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SurfaceKind {
+    #[default]
+    Wall,
+    Floor,
+}
+
+/// Textures for the surface material.
+#[derive(Reflect, Default)]
+pub struct SurfaceTextures {
+    pub base_color: AssetPath<'static>,
+    pub normal_map: Option<AssetPath<'static>>,
+    pub metallic_roughness: Option<AssetPath<'static>>,
+    pub occlusion: Option<AssetPath<'static>>,
+}
+
+impl SurfaceTextures {
+    pub fn iter(&self) -> impl Iterator<Item = &AssetPath<'static>> {
+        [
+            Some(&self.base_color),
+            self.normal_map.as_ref(),
+            self.metallic_roughness.as_ref(),
+            self.occlusion.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut AssetPath<'static>> {
+        [
+            Some(&mut self.base_color),
+            self.normal_map.as_mut(),
+            self.metallic_roughness.as_mut(),
+            self.occlusion.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
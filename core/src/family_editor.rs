@@ -2,7 +2,7 @@ use std::f32::consts::FRAC_PI_2;
 
 use bevy::prelude::*;
 
-use crate::state::GameState;
+use crate::{state::GameState, world::character::Character};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(GameState::FamilyEditor), spawn);
@@ -25,4 +25,5 @@ fn spawn(
         },
         Transform::from_xyz(4.0, 8.0, 4.0),
     ));
+    commands.spawn((Character, DespawnOnExit(GameState::FamilyEditor)));
 }
@@ -12,6 +12,8 @@ const WORLD_EXTENSION: &str = "ron";
 #[derive(Resource)]
 pub struct GamePaths {
     pub worlds: PathBuf,
+    pub settings: PathBuf,
+    pub mods: PathBuf,
 }
 
 impl GamePaths {
@@ -61,6 +63,15 @@ impl Default for GamePaths {
         fs::create_dir_all(&worlds)
             .unwrap_or_else(|e| panic!("{worlds:?} should be writable: {e}"));
 
-        Self { worlds }
+        let settings = config_dir.join("settings.ron");
+
+        let mods = config_dir.join("mods");
+        fs::create_dir_all(&mods).unwrap_or_else(|e| panic!("{mods:?} should be writable: {e}"));
+
+        Self {
+            worlds,
+            settings,
+            mods,
+        }
     }
 }
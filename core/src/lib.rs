@@ -2,8 +2,14 @@ pub mod asset_manifest;
 pub mod error_event;
 pub mod family_editor;
 pub mod game_paths;
+pub mod localization;
+pub mod mods;
 pub mod network;
+pub mod replay;
+pub mod settings;
 pub mod state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_session;
 pub mod undo;
 pub mod world;
 
@@ -17,7 +23,10 @@ impl Plugin for SimgineCorePlugin {
             asset_manifest::plugin,
             family_editor::plugin,
             game_paths::plugin,
+            localization::plugin,
             network::plugin,
+            replay::plugin,
+            settings::plugin,
             state::plugin,
             undo::plugin,
             world::plugin,
@@ -0,0 +1,248 @@
+use std::fmt::Display;
+
+use bevy::{asset::io::AssetSourceId, platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{asset_manifest::locale::LocaleManifest, mods::Mods, settings::Settings};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Localization>()
+        .add_systems(Update, update);
+}
+
+/// Rebuilds translations when locales are loaded or the language is changed.
+fn update(
+    mut asset_events: MessageReader<AssetEvent<LocaleManifest>>,
+    mut localization: ResMut<Localization>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    mods: Res<Mods>,
+    locales: Res<Assets<LocaleManifest>>,
+) {
+    let reloaded = asset_events.read().count() > 0;
+    if !reloaded && !settings.is_changed() {
+        return;
+    }
+
+    let mut locales: Vec<_> = locales
+        .iter()
+        .map(|(id, locale)| (asset_server.get_path(id), locale))
+        .collect();
+
+    // Apply the base game first and then mods in load order to let later ones override strings.
+    locales.sort_by_cached_key(|(path, _)| {
+        path.as_ref()
+            .map(|path| (source_order(&mods, path.source()), path.to_string()))
+    });
+
+    let language = &settings.locale.language;
+    debug!("using '{language}' from {} locales", locales.len());
+
+    let mut new_localization = Localization {
+        language: language.clone(),
+        ..Default::default()
+    };
+    for (_, locale) in locales {
+        if !new_localization
+            .languages
+            .iter()
+            .any(|(code, _)| *code == locale.language)
+        {
+            new_localization
+                .languages
+                .push((locale.language.clone(), locale.name.clone()));
+        }
+
+        if locale.language == *language {
+            new_localization.strings.extend(locale.strings.clone());
+        } else if locale.language == FALLBACK_LANGUAGE {
+            new_localization.fallback.extend(locale.strings.clone());
+        }
+    }
+    new_localization.languages.sort();
+
+    if !new_localization.languages.is_empty()
+        && !new_localization
+            .languages
+            .iter()
+            .any(|(code, _)| code == language)
+    {
+        warn!("language '{language}' is not available, falling back to '{FALLBACK_LANGUAGE}'");
+    }
+
+    *localization = new_localization;
+}
+
+/// Returns the position of the source in the load order.
+///
+/// The base game goes first and unknown sources go last.
+fn source_order(mods: &Mods, source: &AssetSourceId) -> usize {
+    match source {
+        AssetSourceId::Default => 0,
+        AssetSourceId::Name(name) => mods
+            .iter()
+            .position(|mod_info| mod_info.name == **name)
+            .map(|index| index + 1)
+            .unwrap_or(usize::MAX),
+    }
+}
+
+/// Language used when a string is missing for the selected language.
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Translated strings for the selected language.
+#[derive(Resource, Default)]
+pub struct Localization {
+    language: String,
+
+    /// Codes and names of all available languages.
+    languages: Vec<(String, String)>,
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Returns translation for the key.
+    ///
+    /// Falls back to [`FALLBACK_LANGUAGE`] and then to the key itself.
+    /// The latter allows using English manifest fields as keys.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    /// Like [`Self::get`], but replaces `{name}` placeholders with arguments.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+
+        text
+    }
+
+    /// Code of the selected language.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Iterates over codes and names of available languages.
+    pub fn languages(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.languages
+            .iter()
+            .map(|(code, name)| (code.as_str(), name.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LocaleSettings {
+    /// Code of the language from [`LocaleManifest::language`].
+    pub language: String,
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self {
+            language: FALLBACK_LANGUAGE.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+    use crate::mods::ModInfo;
+
+    #[test]
+    fn fallback() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<LocaleManifest>()
+            .init_resource::<Mods>()
+            .insert_resource(Settings {
+                locale: LocaleSettings {
+                    language: "de".to_string(),
+                },
+                ..Default::default()
+            })
+            .add_plugins(plugin);
+
+        let mut locales = app.world_mut().resource_mut::<Assets<LocaleManifest>>();
+        locales.add(LocaleManifest {
+            language: "en".to_string(),
+            name: "English".to_string(),
+            strings: [
+                ("menu.play", "Play"),
+                ("menu.exit", "Exit"),
+                ("loading", "Loading {count} files"),
+            ]
+            .into_iter()
+            .map(|(key, text)| (key.to_string(), text.to_string()))
+            .collect(),
+            ..Default::default()
+        });
+        locales.add(LocaleManifest {
+            language: "de".to_string(),
+            name: "Deutsch".to_string(),
+            strings: [("menu.play", "Spielen"), ("Chair", "Stuhl")]
+                .into_iter()
+                .map(|(key, text)| (key.to_string(), text.to_string()))
+                .collect(),
+            ..Default::default()
+        });
+
+        app.update();
+
+        let localization = app.world().resource::<Localization>();
+        assert_eq!(localization.language(), "de");
+        assert_eq!(localization.get("menu.play"), "Spielen");
+        assert_eq!(localization.get("menu.exit"), "Exit");
+        assert_eq!(localization.get("Chair"), "Stuhl");
+        assert_eq!(localization.get("Table"), "Table");
+        assert_eq!(
+            localization.format("loading", &[("count", &3)]),
+            "Loading 3 files"
+        );
+        assert!(
+            localization
+                .languages()
+                .eq([("de", "Deutsch"), ("en", "English")])
+        );
+    }
+
+    #[test]
+    fn mods_order() {
+        let mods = Mods(
+            ["b", "a"]
+                .into_iter()
+                .map(|name| ModInfo {
+                    name: name.to_string(),
+                    path: name.into(),
+                })
+                .collect(),
+        );
+
+        let mut sources = [
+            AssetSourceId::from("a"),
+            AssetSourceId::from("missing"),
+            AssetSourceId::Default,
+            AssetSourceId::from("b"),
+        ];
+        sources.sort_by_key(|source| source_order(&mods, source));
+        assert_eq!(
+            sources,
+            [
+                AssetSourceId::Default,
+                AssetSourceId::from("b"),
+                AssetSourceId::from("a"),
+                AssetSourceId::from("missing"),
+            ]
+        );
+    }
+}
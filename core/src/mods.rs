@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::io::{AssetSourceBuilder, AssetSourceId, file::FileAssetReader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{game_paths::GamePaths, settings::Settings};
+
+/// Discovers user mods and registers an asset source for each of them.
+///
+/// Each directory inside [`GamePaths::mods`] is a mod and accessible
+/// via asset source with its name, e.g. `my_mod://objects/chair.object.ron`.
+///
+/// Asset sources can't be added after [`AssetPlugin`], so this plugin needs
+/// to be added before it. Add [`LogPlugin`](bevy::log::LogPlugin) earlier
+/// to see discovery messages.
+pub fn plugin(app: &mut App) {
+    app.init_resource::<GamePaths>().init_resource::<Settings>();
+
+    let paths = app.world().resource::<GamePaths>();
+    let settings = app.world().resource::<Settings>();
+    let mods = Mods(order(discover(&paths.mods), &settings.mods));
+
+    for mod_info in &*mods {
+        info!(
+            "registering mod '{}' from {:?}",
+            mod_info.name, mod_info.path
+        );
+        app.register_asset_source(
+            AssetSourceId::Name(mod_info.name.clone().into()),
+            AssetSourceBuilder::platform_default(&mod_info.path.to_string_lossy(), None),
+        );
+    }
+
+    app.insert_resource(mods);
+}
+
+fn discover(mods_dir: &Path) -> Vec<ModInfo> {
+    let entries = match fs::read_dir(mods_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("unable to read {mods_dir:?}: {e}");
+            return Vec::new();
+        }
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                warn!("ignoring mod at {path:?} with non-UTF-8 name");
+                return None;
+            };
+
+            Some(ModInfo {
+                name: name.to_string(),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Removes disabled mods and sorts the rest according to the load order.
+///
+/// Mods from [`ModSettings::load_order`] go first in the listed order,
+/// all other mods follow in alphabetical order.
+fn order(mut mods: Vec<ModInfo>, settings: &ModSettings) -> Vec<ModInfo> {
+    mods.retain(|mod_info| {
+        let disabled = settings.disabled.contains(&mod_info.name);
+        if disabled {
+            info!("skipping disabled mod '{}'", mod_info.name);
+        }
+        !disabled
+    });
+
+    for name in &settings.load_order {
+        if !mods.iter().any(|mod_info| &mod_info.name == name) {
+            warn!("mod '{name}' from load order is not installed");
+        }
+    }
+
+    mods.sort_by_cached_key(|mod_info| {
+        let position = settings
+            .load_order
+            .iter()
+            .position(|name| *name == mod_info.name);
+        (position.is_none(), position, mod_info.name.clone())
+    });
+
+    mods
+}
+
+/// Enabled mods in load order.
+///
+/// Assets from later mods override assets with the same path from earlier mods
+/// and the base game.
+#[derive(Resource, Deref, Default)]
+pub struct Mods(pub(crate) Vec<ModInfo>);
+
+impl Mods {
+    /// Returns the directory on disk for the asset source.
+    ///
+    /// The default source points to [`base_assets_dir`].
+    pub fn source_dir(&self, source: &AssetSourceId) -> Option<PathBuf> {
+        match source {
+            AssetSourceId::Default => Some(base_assets_dir()),
+            AssetSourceId::Name(name) => self
+                .iter()
+                .find(|mod_info| mod_info.name == **name)
+                .map(|mod_info| mod_info.path.clone()),
+        }
+    }
+}
+
+/// Returns the directory with assets shipped with the game.
+pub fn base_assets_dir() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModInfo {
+    /// Directory name, also used as the asset source name.
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct ModSettings {
+    /// Mods that should be loaded first, in the specified order.
+    pub load_order: Vec<String>,
+
+    /// Mods that shouldn't be loaded.
+    pub disabled: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn load_order() {
+        let mods = ["d", "c", "b", "a"]
+            .into_iter()
+            .map(|name| ModInfo {
+                name: name.to_string(),
+                path: PathBuf::from(name),
+            })
+            .collect();
+        let settings = ModSettings {
+            load_order: vec!["c".to_string(), "missing".to_string(), "d".to_string()],
+            disabled: vec!["b".to_string()],
+        };
+
+        let names: Vec<_> = order(mods, &settings)
+            .into_iter()
+            .map(|mod_info| mod_info.name)
+            .collect();
+        assert_eq!(names, ["c", "d", "a"]);
+    }
+}
@@ -1,10 +1,11 @@
 use std::{
+    fmt::{self, Display, Formatter},
     net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
     time::{Duration, SystemTime},
 };
 
 use bevy::{prelude::*, time::common_conditions::*};
-use bevy_replicon::{prelude::*, server};
+use bevy_replicon::{prelude::*, server, shared::backend::connected_client::NetworkId};
 use bevy_replicon_renet::{
     RenetChannelsExt, RenetClient, RenetServer,
     netcode::{
@@ -13,6 +14,7 @@ use bevy_replicon_renet::{
     },
     renet::ConnectionConfig,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::error_event::trigger_error;
 
@@ -91,6 +93,7 @@ fn connect(
 
     commands.insert_resource(transport);
     commands.insert_resource(client);
+    commands.insert_resource(LocalNetworkId(NetworkId::new(client_id)));
 
     Ok(())
 }
@@ -104,6 +107,7 @@ fn disconnect(
     transport.disconnect();
     commands.remove_resource::<NetcodeClientTransport>();
     commands.remove_resource::<RenetClient>();
+    commands.remove_resource::<LocalNetworkId>();
 }
 
 #[derive(Event)]
@@ -122,3 +126,41 @@ pub struct Connect {
 
 #[derive(Event)]
 pub struct Disconnect;
+
+/// Network ID of this client.
+///
+/// Available only while connected to a server. Stored separately from the transport
+/// to let game logic work with any backend.
+#[derive(Resource, Deref, Clone, Copy)]
+pub struct LocalNetworkId(pub NetworkId);
+
+/// Identifies a player across the network.
+///
+/// Uses network IDs for clients since their entities are meaningful only on the server.
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum PlayerId {
+    #[default]
+    Host,
+    Client(u64),
+}
+
+impl PlayerId {
+    pub(crate) fn new(client_id: ClientId, clients: &Query<&NetworkId>) -> Self {
+        match client_id {
+            ClientId::Server => PlayerId::Host,
+            ClientId::Client(client) => {
+                let network_id = clients.get(client).unwrap();
+                PlayerId::Client(network_id.get())
+            }
+        }
+    }
+}
+
+impl Display for PlayerId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerId::Host => f.write_str("Host"),
+            PlayerId::Client(id) => write!(f, "Player {id}"),
+        }
+    }
+}
@@ -0,0 +1,529 @@
+mod checksum;
+
+use std::{any, fs, mem, path::PathBuf, time::Duration};
+
+use bevy::{
+    ecs::entity::{EntityHashMap, MapEntities},
+    platform::collections::HashMap,
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_replicon::{
+    prelude::*, shared::backend::connected_client::NetworkId, world_serialization,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{error_event::trigger_error, state::GameState, world::deserialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayEvents>()
+        .add_observer(start_recording)
+        .add_observer(play.pipe(trigger_error))
+        .add_observer(track_spawn)
+        .add_systems(
+            OnEnter(GameState::World),
+            take_snapshot
+                .run_if(resource_exists::<Recording>)
+                .run_if(not(in_state(ClientState::Connected))),
+        )
+        .add_systems(
+            OnExit(GameState::World),
+            save.pipe(trigger_error)
+                .run_if(resource_exists::<Recording>),
+        )
+        .add_systems(
+            Update,
+            play_events
+                .run_if(resource_exists::<Playback>)
+                .run_if(in_state(GameState::World)),
+        )
+        .add_systems(
+            Last,
+            (
+                record_frame.run_if(resource_exists::<Recording>),
+                verify_frame.run_if(resource_exists::<Playback>),
+            )
+                .run_if(in_state(GameState::World)),
+        );
+}
+
+/// How often checksums are stored in recorded frames.
+const CHECKSUM_INTERVAL: usize = 60;
+
+fn start_recording(start: On<StartRecording>, mut commands: Commands) {
+    info!("recording replay to {:?}", start.path);
+    commands.insert_resource(Recording {
+        path: start.path.clone(),
+        replay: None,
+        entities: Default::default(),
+        events: Default::default(),
+    });
+}
+
+fn take_snapshot(world: &mut World) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let mut dyn_world = DynamicWorld::default();
+    world_serialization::replicate_into(&mut dyn_world, world);
+    let serialized = dyn_world
+        .serialize(&registry.read())
+        .expect("world should be serializable");
+
+    let entities = dyn_world.entities.iter().map(|e| e.entity).collect();
+    debug!("taking snapshot with {} entities", dyn_world.entities.len());
+
+    let mut recording = world.resource_mut::<Recording>();
+    recording.entities = entities;
+    recording.replay = Some(Replay {
+        world: serialized,
+        entities: Default::default(),
+        frames: Default::default(),
+    });
+}
+
+fn record_frame(world: &mut World) {
+    let delta = world.resource::<Time<Real>>().delta();
+    let recording = world.resource::<Recording>();
+    let Some(replay) = &recording.replay else {
+        return;
+    };
+
+    let checksum = replay
+        .frames
+        .len()
+        .is_multiple_of(CHECKSUM_INTERVAL)
+        .then(|| checksum::calculate(world, &recording.entities));
+
+    let mut recording = world.resource_mut::<Recording>();
+    let events = mem::take(&mut recording.events);
+    let replay = recording.replay.as_mut().unwrap();
+    replay.frames.push(ReplayFrame {
+        delta,
+        events,
+        checksum,
+    });
+}
+
+fn save(mut commands: Commands, mut recording: ResMut<Recording>) -> Result<()> {
+    commands.remove_resource::<Recording>();
+
+    let Some(mut replay) = recording.replay.take() else {
+        debug!("ignoring recording without snapshot");
+        return Ok(());
+    };
+
+    // Drop frames after the last checksum since they can't be verified.
+    let verified_len = replay
+        .frames
+        .iter()
+        .rposition(|frame| frame.checksum.is_some())
+        .map(|index| index + 1)
+        .unwrap_or_default();
+    replay.frames.truncate(verified_len);
+    replay.entities = mem::take(&mut recording.entities);
+
+    info!(
+        "saving replay with {} frames to {:?}",
+        replay.frames.len(),
+        recording.path
+    );
+
+    let serialized =
+        ron::to_string(&replay).map_err(|e| format!("unable to serialize replay: {e}"))?;
+    fs::write(&recording.path, serialized)
+        .map_err(|e| format!("unable to save replay to {:?}: {e}", recording.path))?;
+
+    Ok(())
+}
+
+fn play(play: On<PlayReplay>, mut commands: Commands) -> Result<()> {
+    info!("playing replay {:?}", play.path);
+
+    let bytes =
+        fs::read(&play.path).map_err(|e| format!("unable to read replay {:?}: {e}", play.path))?;
+    let replay: Replay = ron::de::from_bytes(&bytes)
+        .map_err(|e| format!("unable to parse replay {:?}: {e}", play.path))?;
+
+    commands.queue(|world: &mut World| {
+        if let Err(e) = spawn_snapshot(world, replay) {
+            error!("unable to play replay: {e}");
+            world.write_message(AppExit::error());
+        }
+    });
+
+    Ok(())
+}
+
+fn spawn_snapshot(world: &mut World, replay: Replay) -> Result<()> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let asset_server = world.resource::<AssetServer>().clone();
+    let dyn_world = deserialize(replay.world.as_bytes(), &registry, &asset_server)?;
+
+    let mut entity_map = EntityHashMap::default();
+    dyn_world
+        .write_to_world(world, &mut entity_map)
+        .map_err(|e| format!("unable to spawn snapshot: {e}"))?;
+
+    let entities = dyn_world
+        .entities
+        .iter()
+        .map(|e| {
+            entity_map
+                .get(&e.entity)
+                .copied()
+                .unwrap_or(Entity::PLACEHOLDER)
+        })
+        .collect();
+
+    if let Some(frame) = replay.frames.first() {
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+    }
+    world.insert_resource(Playback {
+        replay,
+        entity_map,
+        entities,
+        clients: Default::default(),
+        frame: 0,
+        diverged_at: None,
+    });
+
+    Ok(())
+}
+
+/// Assigns spawned replicated entities to their replay indices.
+///
+/// Spawn order is deterministic, so it's used to match
+/// entities from the recorded session with the replayed one.
+fn track_spawn(
+    add: On<Add, Replicated>,
+    recording: Option<ResMut<Recording>>,
+    playback: Option<ResMut<Playback>>,
+) {
+    if let Some(mut recording) = recording
+        && recording.replay.is_some()
+    {
+        trace!("recording spawn of `{}`", add.entity);
+        recording.entities.push(add.entity);
+    } else if let Some(mut playback) = playback {
+        let index = playback.entities.len();
+        let Some(&recorded) = playback.replay.entities.get(index) else {
+            warn!("`{}` wasn't spawned in the recorded session", add.entity);
+            return;
+        };
+
+        trace!("mapping recorded `{recorded}` to `{}`", add.entity);
+        playback.entity_map.insert(recorded, add.entity);
+        playback.entities.push(add.entity);
+    }
+}
+
+fn play_events(world: &mut World) {
+    world.resource_scope(|world, mut playback: Mut<Playback>| {
+        let Some(frame) = playback.replay.frames.get(playback.frame) else {
+            return;
+        };
+
+        for event in frame.events.clone() {
+            let Some(&play_event) = world.resource::<ReplayEvents>().get(&*event.name) else {
+                error!("`{}` is not registered for replays", event.name);
+                continue;
+            };
+
+            let client_id = match event.client {
+                Some(network_id) => {
+                    // Emulate the client with an entity that has only its ID.
+                    let client = *playback
+                        .clients
+                        .entry(network_id)
+                        .or_insert_with(|| world.spawn(NetworkId::new(network_id)).id());
+                    ClientId::Client(client)
+                }
+                None => ClientId::Server,
+            };
+
+            trace!("replaying `{}` from `{client_id:?}`", event.name);
+            if let Err(e) = (play_event)(world, client_id, &event.message, &mut playback.entity_map)
+            {
+                error!("unable to replay `{}`: {e}", event.name);
+            }
+        }
+    });
+}
+
+fn verify_frame(world: &mut World) {
+    world.resource_scope(|world, mut playback: Mut<Playback>| {
+        let index = playback.frame;
+        let Some(frame) = playback.replay.frames.get(index) else {
+            return;
+        };
+
+        if let Some(expected) = frame.checksum {
+            let actual = checksum::calculate(world, &playback.entities);
+            if actual != expected && playback.diverged_at.is_none() {
+                error!("diverged at frame {index}: expected checksum {expected:x}, got {actual:x}");
+                playback.diverged_at = Some(index);
+            }
+        }
+
+        playback.frame += 1;
+        if let Some(next_frame) = playback.replay.frames.get(playback.frame) {
+            world.insert_resource(TimeUpdateStrategy::ManualDuration(next_frame.delta));
+        } else {
+            let exit = match playback.diverged_at {
+                Some(index) => {
+                    error!(
+                        "replay diverged at frame {index} out of {}",
+                        playback.replay.frames.len()
+                    );
+                    AppExit::error()
+                }
+                None => {
+                    info!(
+                        "replay of {} frames matches the recording",
+                        playback.replay.frames.len()
+                    );
+                    AppExit::Success
+                }
+            };
+            world.write_message(exit);
+        }
+    });
+}
+
+pub(crate) trait ReplayAppExt {
+    /// Records client event `E` in replays and registers it for playback.
+    fn record_client_event<E: ReplayableEvent>(&mut self) -> &mut Self;
+}
+
+impl ReplayAppExt for App {
+    fn record_client_event<E: ReplayableEvent>(&mut self) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<ReplayEvents>()
+            .insert(any::type_name::<E>(), play_event::<E>);
+
+        self.add_observer(record_event::<E>)
+    }
+}
+
+fn record_event<E: Event + Serialize>(
+    event: On<FromClient<E>>,
+    recording: Option<ResMut<Recording>>,
+    clients: Query<&NetworkId>,
+) {
+    let Some(mut recording) = recording else {
+        return;
+    };
+    if recording.replay.is_none() {
+        return;
+    }
+
+    let client = match event.client_id {
+        ClientId::Server => None,
+        ClientId::Client(client) => Some(clients.get(client).unwrap().get()),
+    };
+    let message = ron::to_string(&**event).expect("client events should be serializable");
+
+    recording.events.push(ReplayEvent {
+        name: any::type_name::<E>().to_string(),
+        client,
+        message,
+    });
+}
+
+fn play_event<E: ReplayableEvent>(
+    world: &mut World,
+    client_id: ClientId,
+    message: &str,
+    entity_map: &mut EntityHashMap<Entity>,
+) -> Result<()> {
+    let mut message: E = ron::from_str(message)?;
+    message.map_entities(entity_map);
+    message.mark_replayed();
+    world.trigger(FromClient { client_id, message });
+
+    Ok(())
+}
+
+/// Client event that can be recorded in replays.
+pub(crate) trait ReplayableEvent:
+    Event + Serialize + DeserializeOwned + MapEntities
+{
+    /// Marks the event as played from a replay.
+    fn mark_replayed(&mut self);
+}
+
+/// Functions to replay registered client events by their type names.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ReplayEvents(HashMap<&'static str, PlayEventFn>);
+
+type PlayEventFn = fn(&mut World, ClientId, &str, &mut EntityHashMap<Entity>) -> Result<()>;
+
+/// Starts recording a replay.
+///
+/// The snapshot is taken on entering [`GameState::World`] and the replay
+/// is saved to the specified path on exit.
+#[derive(Event)]
+pub struct StartRecording {
+    pub path: PathBuf,
+}
+
+/// Plays a replay from the specified path and exits once it finishes.
+///
+/// The app exits with an error if the state diverges from the recorded checksums.
+#[derive(Event)]
+pub struct PlayReplay {
+    pub path: PathBuf,
+}
+
+#[derive(Resource)]
+struct Recording {
+    path: PathBuf,
+
+    /// Recorded data, available after the snapshot was taken.
+    replay: Option<Replay>,
+
+    /// Replicated entities in their spawn order.
+    entities: Vec<Entity>,
+
+    /// Events for the current frame.
+    events: Vec<ReplayEvent>,
+}
+
+#[derive(Resource)]
+struct Playback {
+    replay: Replay,
+
+    /// Maps entities from the recorded session to the replayed one.
+    entity_map: EntityHashMap<Entity>,
+
+    /// Like [`Recording::entities`], but for the replayed session.
+    entities: Vec<Entity>,
+
+    /// Emulated clients by their network IDs.
+    clients: HashMap<u64, Entity>,
+
+    frame: usize,
+    diverged_at: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Replay {
+    /// Serialized world at the moment of recording start.
+    world: String,
+
+    /// Replicated entities from the recorded session in their spawn order.
+    ///
+    /// Starts with entities from the snapshot.
+    entities: Vec<Entity>,
+
+    frames: Vec<ReplayFrame>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReplayFrame {
+    delta: Duration,
+    events: Vec<ReplayEvent>,
+
+    /// Checksum of the replicated state at the end of the frame.
+    checksum: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ReplayEvent {
+    /// Type name of the event.
+    name: String,
+
+    /// Network ID of the sender or [`None`] if it was sent by the host.
+    client: Option<u64>,
+
+    /// Event serialized in RON.
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use test_log::test;
+
+    use super::*;
+    use crate::test_session::TestSession;
+
+    #[test]
+    fn round_trip() {
+        let path = env::temp_dir().join(format!("simgine_replay_{}.ron", process::id()));
+
+        let mut recording = TestSession::new(0, setup);
+        let app = &mut recording.server;
+        app.world_mut()
+            .trigger(StartRecording { path: path.clone() });
+        let entity = app.world_mut().spawn((Replicated, Counter(0))).id();
+        set_state(app, GameState::World);
+
+        // Record enough frames for the second checksum.
+        for _ in 0..=CHECKSUM_INTERVAL {
+            app.world_mut().trigger(FromClient {
+                client_id: ClientId::Server,
+                message: Increment { entity },
+            });
+            app.update();
+        }
+
+        set_state(app, GameState::Menu);
+        assert!(!app.world().contains_resource::<Recording>());
+
+        let mut playback = TestSession::new(0, setup);
+        let app = &mut playback.server;
+        app.world_mut().trigger(PlayReplay { path: path.clone() });
+        set_state(app, GameState::World);
+
+        let exit = loop {
+            if let Some(exit) = app.should_exit() {
+                break exit;
+            }
+            app.update();
+        };
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(exit, AppExit::Success, "state shouldn't diverge");
+        let mut counters = app.world_mut().query::<&Counter>();
+        let counter = counters.single(app.world()).unwrap();
+        assert_eq!(
+            counter.0 as usize, CHECKSUM_INTERVAL,
+            "frames after the last checksum should be dropped"
+        );
+    }
+
+    fn set_state(app: &mut App, state: GameState) {
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+        app.update();
+    }
+
+    fn setup(app: &mut App) {
+        app.init_state::<GameState>()
+            .replicate::<Counter>()
+            .record_client_event::<Increment>()
+            .add_observer(increment)
+            .add_plugins(plugin);
+    }
+
+    fn increment(increment: On<FromClient<Increment>>, mut counters: Query<&mut Counter>) {
+        let mut counter = counters.get_mut(increment.entity).unwrap();
+        counter.0 += 1;
+    }
+
+    #[derive(Component, Reflect, Serialize, Deserialize)]
+    #[reflect(Component)]
+    struct Counter(u32);
+
+    #[derive(Event, Serialize, Deserialize, MapEntities)]
+    struct Increment {
+        #[entities]
+        entity: Entity,
+    }
+
+    impl ReplayableEvent for Increment {
+        fn mark_replayed(&mut self) {}
+    }
+}
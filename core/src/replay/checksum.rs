@@ -0,0 +1,169 @@
+use std::{
+    fmt::{self, Write},
+    hash::Hasher,
+};
+
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    reflect::{PartialReflect, ReflectRef},
+};
+use bevy_replicon::world_serialization;
+
+/// Calculates checksum of the replicated state.
+///
+/// Entities are hashed independently and combined in sorted order,
+/// so the result doesn't depend on the iteration order. Entity references
+/// inside components are replaced with indices from `entities`.
+pub(super) fn calculate(world: &World, entities: &[Entity]) -> u64 {
+    let mut dyn_world = DynamicWorld::default();
+    world_serialization::replicate_into(&mut dyn_world, world);
+
+    let indices: EntityHashMap<_> = entities
+        .iter()
+        .enumerate()
+        .map(|(index, &entity)| (entity, index))
+        .collect();
+
+    let mut entity_hashes: Vec<_> = dyn_world
+        .entities
+        .iter()
+        .map(|entity| {
+            let mut components: Vec<_> = entity.components.iter().collect();
+            components.sort_by_key(|c| c.reflect_type_path());
+
+            let mut hasher = Fnv::default();
+            for component in components {
+                hasher.write(component.reflect_type_path().as_bytes());
+                hash_reflect(&mut hasher, &**component, &indices);
+            }
+            hasher.finish()
+        })
+        .collect();
+    entity_hashes.sort_unstable();
+
+    let mut hasher = Fnv::default();
+    for hash in entity_hashes {
+        hasher.write_u64(hash);
+    }
+    hasher.finish()
+}
+
+fn hash_reflect(hasher: &mut Fnv, value: &dyn PartialReflect, indices: &EntityHashMap<usize>) {
+    if let Some(entity) = value.try_downcast_ref::<Entity>() {
+        let index = indices.get(entity).map(|&i| i as u64).unwrap_or(u64::MAX);
+        hasher.write_u64(index);
+        return;
+    }
+
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                hasher.write(value.name_at(index).unwrap_or_default().as_bytes());
+                hash_reflect(hasher, field, indices);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                hash_reflect(hasher, field, indices);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                hash_reflect(hasher, field, indices);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                hash_reflect(hasher, item, indices);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                hash_reflect(hasher, item, indices);
+            }
+        }
+        ReflectRef::Map(value) => {
+            // Iteration order is not guaranteed, so combine entries commutatively.
+            let mut sum = 0u64;
+            for (key, value) in value.iter() {
+                let mut entry_hasher = Fnv::default();
+                hash_reflect(&mut entry_hasher, key, indices);
+                hash_reflect(&mut entry_hasher, value, indices);
+                sum = sum.wrapping_add(entry_hasher.finish());
+            }
+            hasher.write_u64(sum);
+        }
+        ReflectRef::Set(value) => {
+            let mut sum = 0u64;
+            for item in value.iter() {
+                let mut item_hasher = Fnv::default();
+                hash_reflect(&mut item_hasher, item, indices);
+                sum = sum.wrapping_add(item_hasher.finish());
+            }
+            hasher.write_u64(sum);
+        }
+        ReflectRef::Enum(value) => {
+            hasher.write(value.variant_name().as_bytes());
+            for field in value.iter_fields() {
+                hash_reflect(hasher, field.value(), indices);
+            }
+        }
+        ReflectRef::Opaque(value) => {
+            write!(hasher, "{value:?}").unwrap();
+        }
+        #[allow(unreachable_patterns, reason = "other variants depend on features")]
+        _ => (),
+    }
+}
+
+/// 64-bit FNV-1a.
+///
+/// Used instead of [`std::hash::DefaultHasher`] because its output is stable between builds.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl Write for Fnv {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Hasher::write(self, s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn fnv() {
+        for (input, expected) in [
+            ("", 0xcbf29ce484222325),
+            ("a", 0xaf63dc4c8601ec8c),
+            ("foobar", 0x85944171f73967e8),
+        ] {
+            let mut hasher = Fnv::default();
+            hasher.write(input.as_bytes());
+            assert_eq!(hasher.finish(), expected, "hash mismatch for '{input}'");
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::{fs, io::ErrorKind};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error_event::trigger_error, game_paths::GamePaths, localization::LocaleSettings,
+    mods::ModSettings,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Settings>()
+        .add_observer(save.pipe(trigger_error));
+}
+
+fn save(_on: On<SaveSettings>, settings: Res<Settings>, paths: Res<GamePaths>) -> Result<()> {
+    info!("saving settings to {:?}", paths.settings);
+
+    let content = ron::ser::to_string_pretty(&*settings, Default::default())
+        .expect("settings should be serializable");
+    fs::write(&paths.settings, content)
+        .map_err(|e| format!("unable to save settings to {:?}: {e}", paths.settings))?;
+
+    Ok(())
+}
+
+/// Game settings loaded from [`GamePaths::settings`].
+///
+/// Missing fields or file fall back to defaults.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub undo: UndoSettings,
+    pub mods: ModSettings,
+    pub locale: LocaleSettings,
+}
+
+impl FromWorld for Settings {
+    fn from_world(world: &mut World) -> Self {
+        let paths = world.resource::<GamePaths>();
+        let content = match fs::read_to_string(&paths.settings) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                debug!("{:?} doesn't exist, using default settings", paths.settings);
+                return Default::default();
+            }
+            Err(e) => {
+                error!("unable to read {:?}: {e}", paths.settings);
+                return Default::default();
+            }
+        };
+
+        match ron::from_str(&content) {
+            Ok(settings) => {
+                info!("loaded settings from {:?}", paths.settings);
+                settings
+            }
+            Err(e) => {
+                error!("unable to parse {:?}: {e}", paths.settings);
+                Default::default()
+            }
+        }
+    }
+}
+
+/// Writes the current [`Settings`] to [`GamePaths::settings`].
+#[derive(Event)]
+pub struct SaveSettings;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct UndoSettings {
+    /// Maximum number of records in each of the undo and redo stacks.
+    pub max_len: usize,
+
+    /// Approximate memory limit in bytes for all stored records.
+    ///
+    /// The oldest records are dropped when exceeded.
+    pub memory_budget: Option<usize>,
+}
+
+impl Default for UndoSettings {
+    fn default() -> Self {
+        Self {
+            max_len: 25,
+            memory_budget: None,
+        }
+    }
+}
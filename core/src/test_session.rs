@@ -0,0 +1,151 @@
+//! Utilities for testing networked logic.
+//!
+//! Available in unit tests and behind the `test-utils` feature for integration tests and benches.
+
+use std::fmt::Debug;
+
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{
+    client::server_entity_map::ServerEntityMap,
+    prelude::*,
+    server,
+    shared::backend::connected_client::{ConnectedClient, NetworkId},
+    test_app::ServerTestAppExt,
+};
+
+use crate::network::LocalNetworkId;
+
+/// Server and clients running in the same process.
+///
+/// Apps exchange messages through the in-memory backend from replicon without sockets.
+pub struct TestSession {
+    pub server: App,
+    pub clients: Vec<App>,
+
+    /// Network IDs of clients on the server, in the same order.
+    network_ids: Vec<NetworkId>,
+}
+
+impl TestSession {
+    /// Creates a server with the specified number of connected clients.
+    ///
+    /// `setup` is called for each app to register the tested logic.
+    pub fn new(clients_count: usize, setup: impl Fn(&mut App)) -> Self {
+        let mut server = create_app(&setup);
+        let mut clients = Vec::with_capacity(clients_count);
+        let mut network_ids = Vec::with_capacity(clients_count);
+        let mut connected = server
+            .world_mut()
+            .query_filtered::<(Entity, Option<&NetworkId>), With<ConnectedClient>>();
+        for index in 0..clients_count {
+            let mut client = create_app(&setup);
+            server.connect_client(&mut client);
+            clients.push(client);
+
+            let (entity, network_id) = connected
+                .iter(server.world())
+                .find(|(_, id)| id.is_none_or(|id| !network_ids.contains(id)))
+                .expect("client should be connected");
+
+            // Game logic identifies players by network IDs, make sure each client has one.
+            let network_id = match network_id {
+                Some(&network_id) => network_id,
+                None => {
+                    let network_id = NetworkId::new(index as u64 + 1);
+                    server.world_mut().entity_mut(entity).insert(network_id);
+                    network_id
+                }
+            };
+            network_ids.push(network_id);
+            clients[index].insert_resource(LocalNetworkId(network_id));
+        }
+
+        let mut session = Self {
+            server,
+            clients,
+            network_ids,
+        };
+        session.update();
+        session
+    }
+
+    /// Runs a full round trip.
+    ///
+    /// Clients send their messages, the server handles them and sends responses
+    /// with replication back to clients.
+    pub fn update(&mut self) {
+        for client in &mut self.clients {
+            client.update();
+            self.server.exchange_with_client(client);
+        }
+
+        self.server.update();
+
+        for client in &mut self.clients {
+            self.server.exchange_with_client(client);
+            client.update();
+        }
+    }
+
+    /// Returns the client app by index.
+    pub fn client(&mut self, index: usize) -> &mut App {
+        &mut self.clients[index]
+    }
+
+    /// Returns the network ID assigned to the client by index.
+    pub fn network_id(&self, index: usize) -> NetworkId {
+        self.network_ids[index]
+    }
+
+    /// Returns the entity on the client by index that corresponds to the server entity.
+    ///
+    /// Panics if the entity isn't replicated to the client.
+    pub fn client_entity(&self, index: usize, server_entity: Entity) -> Entity {
+        let entity_map = self.clients[index].world().resource::<ServerEntityMap>();
+        *entity_map
+            .to_client()
+            .get(&server_entity)
+            .unwrap_or_else(|| panic!("`{server_entity}` should be replicated to client {index}"))
+    }
+
+    /// Asserts that all clients have the same component value as the server entity.
+    pub fn assert_replicated<C: Component + PartialEq + Debug>(&self, server_entity: Entity) {
+        let expected = self
+            .server
+            .world()
+            .get::<C>(server_entity)
+            .unwrap_or_else(|| panic!("`{server_entity}` should have the component"));
+
+        for (index, client) in self.clients.iter().enumerate() {
+            let entity = self.client_entity(index, server_entity);
+            assert_eq!(
+                client.world().get::<C>(entity),
+                Some(expected),
+                "client {index} should have the same value for `{server_entity}`"
+            );
+        }
+    }
+}
+
+fn create_app(setup: &impl Fn(&mut App)) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        AssetPlugin::default(),
+        RepliconPlugins.set(ServerPlugin {
+            tick_schedule: None,
+            ..Default::default()
+        }),
+    ))
+    .add_systems(
+        PostUpdate,
+        server::increment_tick.run_if(in_state(ServerState::Running)),
+    );
+
+    setup(&mut app);
+
+    app.finish();
+    app.cleanup();
+    app
+}
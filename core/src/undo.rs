@@ -1,21 +1,83 @@
+#[cfg(feature = "test-utils")]
+pub mod bench;
 pub(crate) mod client_command;
-pub(crate) mod history;
+pub mod history;
+pub mod revision;
+
+use std::{any::Any, time::Duration};
 
 use bevy::{
     ecs::{
-        entity::{EntityHashMap, MapEntities},
+        entity::{EntityHashMap, EntityMapper, MapEntities},
         system::SystemParam,
     },
     prelude::*,
 };
+use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
+use crate::{settings::Settings, state::GameState};
 use history::{CommandHistory, CommandRecord, HistoryCommand};
 
+/// How long a [`ConfirmableCommand`] can wait for a response from the server.
+pub(crate) const PENDING_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub(super) fn plugin(app: &mut App) {
-    app.init_resource::<CommandHistory>()
-        .init_resource::<CommandIdAllocator>();
+    app.add_plugins((client_command::plugin, revision::plugin))
+        .init_resource::<CommandHistory>()
+        .init_resource::<CommandIdAllocator>()
+        .add_systems(
+            Update,
+            (
+                expire_pending,
+                apply_settings.run_if(resource_exists_and_changed::<Settings>),
+            ),
+        )
+        .add_systems(OnExit(ClientState::Connected), clear_pending)
+        .add_systems(OnExit(GameState::World), clear_history);
+}
+
+fn apply_settings(settings: Res<Settings>, mut history: ResMut<CommandHistory>) {
+    debug!("applying {:?}", settings.undo);
+    history.set_limits(settings.undo.max_len, settings.undo.memory_budget);
+}
+
+/// Clears the history since commands reference entities from the left world.
+fn clear_history(mut history: ResMut<CommandHistory>) {
+    debug!("clearing history");
+    history.clear();
+}
+
+fn expire_pending(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut history: ResMut<CommandHistory>,
+) {
+    let Some(deadline) = time.elapsed().checked_sub(PENDING_TIMEOUT) else {
+        return;
+    };
+
+    for ids in history.expired(deadline) {
+        warn!("`{ids:?}` wasn't answered within {PENDING_TIMEOUT:?}, dropping");
+        if let Some(rollback) = history.deny(ids[0]) {
+            commands.queue(rollback);
+        }
+        for id in ids {
+            commands.trigger(CommandExpired { id });
+        }
+    }
+}
+
+fn clear_pending(mut commands: Commands, mut history: ResMut<CommandHistory>) {
+    let ids = history.clear_pending();
+    if !ids.is_empty() {
+        debug!("dropping {} pending commands after disconnect", ids.len());
+    }
+
+    for id in ids {
+        commands.trigger(CommandExpired { id });
+    }
 }
 
 #[derive(SystemParam, Deref, DerefMut)]
@@ -32,6 +94,7 @@ impl HistoryCommands<'_, '_> {
         self.queue(ApplyReverisbleCommand {
             command: Box::new(command),
             entities: Default::default(),
+            label: None,
             source: CommandSource::User,
         });
     }
@@ -44,17 +107,50 @@ impl HistoryCommands<'_, '_> {
             id,
             command: Box::new(command),
             entities: Default::default(),
+            label: None,
             source: CommandSource::User,
         });
         id
     }
 
-    /// Reverses the most recent command queued with [`Self::queue_reversible`].
-    pub fn undo(&mut self) {
+    /// Starts a transaction that groups all subsequently queued commands into a single history entry.
+    ///
+    /// The group is undone and redone as a whole. If it contains confirmable commands, it enters
+    /// the history only after all of them are confirmed, and a denial of any of them reverts
+    /// the others.
+    ///
+    /// Must be paired with [`Self::end_group`]. Nested groups are merged into the outermost one.
+    pub(crate) fn begin_group(&mut self) {
+        self.queue(|world: &mut World| {
+            let mut history = world.resource_mut::<CommandHistory>();
+            history.begin_group(None, CommandSource::User);
+        });
+    }
+
+    /// Closes the transaction opened by [`Self::begin_group`].
+    pub(crate) fn end_group(&mut self) {
         self.queue(|world: &mut World| {
             let mut history = world.resource_mut::<CommandHistory>();
-            if let Some(record) = history.pop_undo() {
-                info!("undo");
+            history.end_group();
+        });
+    }
+
+    /// Reverses the most recent command queued with [`Self::queue_reversible`].
+    pub fn undo(&mut self) {
+        self.undo_steps(1);
+    }
+
+    /// Like [`Self::undo`], but reverses the specified number of most recent commands.
+    ///
+    /// Used to jump to a specific point in [`CommandHistory::undo_labels`].
+    pub fn undo_steps(&mut self, steps: usize) {
+        self.queue(move |world: &mut World| {
+            for _ in 0..steps {
+                let mut history = world.resource_mut::<CommandHistory>();
+                let Some(record) = history.pop_undo() else {
+                    break;
+                };
+                info!("undo `{}`", record.label);
                 apply_record(world, record, CommandSource::Undo);
             }
         });
@@ -62,10 +158,20 @@ impl HistoryCommands<'_, '_> {
 
     /// Reapplies the most recently undone command, if any.
     pub fn redo(&mut self) {
-        self.queue(|world: &mut World| {
-            let mut history = world.resource_mut::<CommandHistory>();
-            if let Some(record) = history.pop_redo() {
-                info!("redo");
+        self.redo_steps(1);
+    }
+
+    /// Like [`Self::redo`], but reapplies the specified number of most recently undone commands.
+    ///
+    /// Used to jump to a specific point in [`CommandHistory::redo_labels`].
+    pub fn redo_steps(&mut self, steps: usize) {
+        self.queue(move |world: &mut World| {
+            for _ in 0..steps {
+                let mut history = world.resource_mut::<CommandHistory>();
+                let Some(record) = history.pop_redo() else {
+                    break;
+                };
+                info!("redo `{}`", record.label);
                 apply_record(world, record, CommandSource::Redo);
             }
         });
@@ -78,6 +184,7 @@ fn apply_record(world: &mut World, record: CommandRecord, source: CommandSource)
             let command = ApplyReverisbleCommand {
                 command,
                 entities: record.entities,
+                label: Some(record.label),
                 source,
             };
             command.apply(world);
@@ -89,10 +196,37 @@ fn apply_record(world: &mut World, record: CommandRecord, source: CommandSource)
                 id,
                 command,
                 entities: record.entities,
+                label: Some(record.label),
                 source,
             };
             command.apply(world);
         }
+        HistoryCommand::Group(records) => {
+            // Apply inverted commands in reverse order and collect them into a new group.
+            world
+                .resource_mut::<CommandHistory>()
+                .begin_group(Some(record.label), source);
+            for record in records.into_iter().rev() {
+                apply_record(world, record, source);
+            }
+            world.resource_mut::<CommandHistory>().end_group();
+        }
+    }
+}
+
+/// Reverts already applied members of a group with a denied command.
+///
+/// Returned by [`CommandHistory::deny`].
+pub(crate) struct Rollback(Vec<CommandRecord>);
+
+impl Command for Rollback {
+    type Out = ();
+
+    fn apply(self, world: &mut World) {
+        debug!("rolling back {} commands", self.0.len());
+        for record in self.0.into_iter().rev() {
+            apply_record(world, record, CommandSource::Rollback);
+        }
     }
 }
 
@@ -110,8 +244,29 @@ impl CommandIdAllocator {
     }
 }
 
+/// Triggered when a pending [`ConfirmableCommand`] is dropped without a response from the server.
+///
+/// Happens on timeout or disconnect. Placeholders created for the command should be removed.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct CommandExpired {
+    pub(crate) id: CommandId,
+}
+
 /// ID for a [`ConfirmableCommand`].
-#[derive(Component, Default, Debug, Serialize, Deserialize, Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(
+    Component,
+    Default,
+    Debug,
+    Serialize,
+    Deserialize,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+)]
 pub struct CommandId(u64);
 
 /// Applies a command that will be tracked in [`CommandHistory`].
@@ -122,6 +277,9 @@ struct ApplyReverisbleCommand {
     /// Entities created by the previous inverted command, if any.
     entities: RecordedEntities,
 
+    /// Label of the original command or [`None`] to take it from [`Self::command`].
+    label: Option<String>,
+
     /// Who triggered the command.
     source: CommandSource,
 }
@@ -133,15 +291,19 @@ impl Command for ApplyReverisbleCommand {
         let name = self.command.dyn_name();
         debug!("applying `{name}`");
 
+        // Evaluate before applying since the command may despawn referenced entities.
+        let label = self.label.unwrap_or_else(|| self.command.label(world));
+
         let mut recorder = EntityRecorder::new(&mut self.entities);
         let Some(inverted) = self.command.apply(&mut recorder, world) else {
             debug!("unable to apply `{name}`");
             return;
         };
 
+        let applied_at = world.resource::<Time<Real>>().elapsed();
         let mut history = world.resource_mut::<CommandHistory>();
         history.flush_entity_mappings(&mut recorder.queued_mappings);
-        history.push_reversible(inverted, self.entities, self.source);
+        history.push_reversible(inverted, self.entities, label, self.source, applied_at);
     }
 }
 
@@ -156,6 +318,9 @@ struct ApplyConfirmableCommand {
     /// Entities created by the previous inverted command, if any.
     entities: RecordedEntities,
 
+    /// Label of the original command or [`None`] to take it from [`Self::command`].
+    label: Option<String>,
+
     /// Who triggered the command.
     source: CommandSource,
 }
@@ -167,15 +332,25 @@ impl Command for ApplyConfirmableCommand {
         let name = self.command.dyn_name();
         debug!("applying `{name}` with `{:?}`", self.id);
 
+        let label = self.label.unwrap_or_else(|| self.command.label(world));
+
         let mut recorder = EntityRecorder::new(&mut self.entities);
         let Some(inverted) = self.command.apply(self.id, &mut recorder, world) else {
             debug!("unable to apply `{name}`");
             return;
         };
 
+        let sent_at = world.resource::<Time<Real>>().elapsed();
         let mut history = world.resource_mut::<CommandHistory>();
         history.flush_entity_mappings(&mut recorder.queued_mappings);
-        history.push_confirmable(self.id, inverted, self.entities, self.source);
+        history.push_confirmable(
+            self.id,
+            inverted,
+            self.entities,
+            label,
+            self.source,
+            sent_at,
+        );
     }
 }
 
@@ -184,6 +359,8 @@ enum CommandSource {
     User,
     Undo,
     Redo,
+    /// Reverting a group with a denied command, not recorded in the history.
+    Rollback,
 }
 
 /// Records entity changes in commands.
@@ -244,6 +421,33 @@ pub trait ReversibleCommand: DynReversible + Send + Sync + 'static {
         recorder: &mut EntityRecorder,
         world: &mut World,
     ) -> Option<Box<dyn ReversibleCommand>>;
+
+    /// Returns a human-readable description displayed in the history, like "Move Table".
+    ///
+    /// Called before [`Self::apply`].
+    fn label(&self, _world: &World) -> String {
+        self.dyn_name().to_string()
+    }
+
+    /// Merges the inverted command of a newer action into this inverted command.
+    ///
+    /// Called for the most recent history record when a new command is applied shortly after it.
+    /// `newer` can be downcasted to check its type. Returns `true` if merged, in which case
+    /// `newer` is dropped and both actions are undone in a single step.
+    ///
+    /// Useful for continuous edits, like moving the same entity multiple times.
+    /// Since `newer` is dropped, only commands that don't record entities should be merged.
+    /// Does nothing by default.
+    fn merge(&mut self, _newer: &dyn Any) -> bool {
+        false
+    }
+
+    /// Returns the number of bytes the command owns on the heap, like string contents.
+    ///
+    /// Counted towards the memory budget of the history. Returns 0 by default.
+    fn heap_size(&self) -> usize {
+        0
+    }
 }
 
 /// Like [`ReversibleCommand`], but requires confirmation before being considered applied.
@@ -257,22 +461,44 @@ pub trait ConfirmableCommand: DynReversible + Send + Sync + 'static {
         recorder: &mut EntityRecorder,
         world: &mut World,
     ) -> Option<Box<dyn ConfirmableCommand>>;
+
+    /// Like [`ReversibleCommand::label`].
+    fn label(&self, _world: &World) -> String {
+        self.dyn_name().to_string()
+    }
+
+    /// Like [`ReversibleCommand::merge`].
+    ///
+    /// Called only after confirmation of both commands.
+    fn merge(&mut self, _newer: &dyn Any) -> bool {
+        false
+    }
+
+    /// Like [`ReversibleCommand::heap_size`].
+    fn heap_size(&self) -> usize {
+        0
+    }
 }
 
-/// Helper for [`ReversibleCommand`] to auto-implement name info and entity mapping
+/// Helper for [`ReversibleCommand`] to auto-implement name info, entity mapping and downcasting
 /// without requiring [`MapEntities`] (which is not dyn-compatible).
 pub trait DynReversible {
     fn dyn_name(&self) -> ShortName<'static>;
-    fn dyn_map_entities(&mut self, map: &mut EntityHashMap<Entity>);
+    fn dyn_map_entities(&mut self, mapper: &mut dyn EntityMapper);
+    fn as_any(&self) -> &dyn Any;
 }
 
-impl<T: MapEntities> DynReversible for T {
+impl<T: MapEntities + 'static> DynReversible for T {
     fn dyn_name(&self) -> ShortName<'static> {
         ShortName::of::<T>()
     }
 
-    fn dyn_map_entities(&mut self, mapper: &mut EntityHashMap<Entity>) {
-        self.map_entities(mapper);
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_map_entities(&mut self, mut mapper: &mut dyn EntityMapper) {
+        self.map_entities(&mut mapper);
     }
 }
 
@@ -280,10 +506,17 @@ impl<T: MapEntities> DynReversible for T {
 mod tests {
     use std::mem;
 
-    use bevy::ecs::{entity::MapEntities, system::SystemState};
+    use bevy::{
+        ecs::{
+            entity::MapEntities,
+            system::{RunSystemOnce, SystemState},
+        },
+        time::TimeUpdateStrategy,
+    };
     use test_log::test;
 
     use super::*;
+    use client_command::DespawnOnResponse;
 
     #[test]
     fn translate() {
@@ -318,6 +551,79 @@ mod tests {
         assert_eq!(transform.translation, Vec3::ONE);
     }
 
+    #[test]
+    fn merge() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let entity = app.world_mut().spawn(Transform::default()).id();
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.queue_reversible(Translate {
+            entity,
+            translation: Vec3::ONE,
+        });
+        commands.queue_reversible(Translate {
+            entity,
+            translation: Vec3::splat(2.0),
+        });
+        state.apply(app.world_mut());
+
+        assert_eq!(
+            app.world().resource::<CommandHistory>().undo_labels().len(),
+            1
+        );
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        let transform = app.world_mut().get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::ZERO);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.redo();
+        state.apply(app.world_mut());
+
+        let transform = app.world_mut().get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::splat(2.0));
+    }
+
+    #[test]
+    fn merge_window() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin)).insert_resource(
+            TimeUpdateStrategy::ManualDuration(history::MERGE_WINDOW * 2),
+        );
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let entity = app.world_mut().spawn(Transform::default()).id();
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.queue_reversible(Translate {
+            entity,
+            translation: Vec3::ONE,
+        });
+        state.apply(app.world_mut());
+
+        app.update();
+        app.update();
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.queue_reversible(Translate {
+            entity,
+            translation: Vec3::splat(2.0),
+        });
+        state.apply(app.world_mut());
+
+        assert_eq!(
+            app.world().resource::<CommandHistory>().undo_labels().len(),
+            2,
+            "commands outside of the window shouldn't merge"
+        );
+    }
+
     #[test]
     fn spawn_despawn() {
         let mut app = App::new();
@@ -489,6 +795,285 @@ mod tests {
         );
     }
 
+    #[test]
+    fn memory_budget() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .insert_resource(CommandHistory::new(25).with_memory_budget(0));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.queue_reversible(Spawn::default());
+        commands.queue_reversible(Spawn::default());
+        state.apply(app.world_mut());
+
+        let history = app.world().resource::<CommandHistory>();
+        assert_eq!(
+            history.undo_labels().len(),
+            1,
+            "the last record should be kept even over the budget"
+        );
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 1);
+    }
+
+    #[test]
+    fn group() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.begin_group();
+        commands.queue_reversible(Spawn::default());
+        commands.queue_reversible(Spawn::default());
+        commands.end_group();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 2);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        assert_eq!(transforms.iter(app.world()).count(), 0);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.redo();
+        state.apply(app.world_mut());
+
+        assert_eq!(transforms.iter(app.world()).len(), 2);
+    }
+
+    #[test]
+    fn pending_group_deny() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .init_resource::<LastUndoId>();
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.begin_group();
+        let first_id = commands.queue_confirmable(PendingSpawn::default());
+        let second_id = commands.queue_confirmable(PendingSpawn::default());
+        commands.end_group();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 2);
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        history.confirm(first_id);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        assert_eq!(
+            transforms.iter(app.world()).len(),
+            2,
+            "group shouldn't be in history until all members are confirmed"
+        );
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        let rollback = history.deny(second_id).unwrap();
+        rollback.apply(app.world_mut());
+
+        assert_eq!(
+            transforms.iter(app.world()).len(),
+            1,
+            "confirmed member should be rolled back"
+        );
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        assert_eq!(transforms.iter(app.world()).len(), 1);
+    }
+
+    #[test]
+    fn pending_group_deny_partial() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .init_resource::<LastUndoId>();
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.begin_group();
+        let first_id = commands.queue_confirmable(PendingSpawn::default());
+        let second_id = commands.queue_confirmable(PendingSpawn::default());
+        let third_id = commands.queue_confirmable(PendingSpawn::default());
+        commands.end_group();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 3);
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        history.confirm(first_id);
+        let rollback = history.deny(second_id).unwrap();
+        rollback.apply(app.world_mut());
+
+        assert_eq!(
+            transforms.iter(app.world()).len(),
+            2,
+            "only the confirmed member should be rolled back"
+        );
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        history.confirm(third_id);
+        assert_eq!(
+            history.undo_labels().len(),
+            0,
+            "confirmation of a dropped member should be ignored"
+        );
+    }
+
+    #[test]
+    fn pending_timeout() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .init_resource::<LastUndoId>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                PENDING_TIMEOUT + Duration::from_secs(1),
+            ));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        let id = commands.queue_confirmable(PendingSpawn::default());
+        commands.spawn(DespawnOnResponse { id });
+        state.apply(app.world_mut());
+
+        app.update();
+        app.update();
+
+        let mut placeholders = app.world_mut().query::<&DespawnOnResponse>();
+        assert_eq!(placeholders.iter(app.world()).count(), 0);
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        history.confirm(id);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(
+            transforms.iter(app.world()).len(),
+            1,
+            "late confirmation should be ignored"
+        );
+    }
+
+    #[test]
+    fn pending_group_timeout() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .init_resource::<LastUndoId>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(
+                PENDING_TIMEOUT + Duration::from_secs(1),
+            ));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.begin_group();
+        for _ in 0..2 {
+            let id = commands.queue_confirmable(PendingSpawn::default());
+            commands.spawn(DespawnOnResponse { id });
+        }
+        commands.end_group();
+        state.apply(app.world_mut());
+
+        app.update();
+        app.update();
+
+        let mut placeholders = app.world_mut().query::<&DespawnOnResponse>();
+        assert_eq!(
+            placeholders.iter(app.world()).count(),
+            0,
+            "all members should expire"
+        );
+
+        let history = app.world().resource::<CommandHistory>();
+        assert!(history.pending_labels().is_empty());
+    }
+
+    #[test]
+    fn pending_disconnect() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin))
+            .init_resource::<LastUndoId>();
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.begin_group();
+        let id = commands.queue_confirmable(PendingSpawn::default());
+        commands.spawn(DespawnOnResponse { id });
+        commands.end_group();
+        state.apply(app.world_mut());
+
+        app.world_mut().run_system_once(clear_pending).unwrap();
+
+        let mut placeholders = app.world_mut().query::<&DespawnOnResponse>();
+        assert_eq!(placeholders.iter(app.world()).count(), 0);
+
+        let mut history = app.world_mut().resource_mut::<CommandHistory>();
+        history.confirm(id);
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 1);
+    }
+
+    #[test]
+    fn steps() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, plugin));
+
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.queue_reversible(Spawn::default());
+        commands.queue_reversible(Spawn::default());
+        commands.queue_reversible(Spawn::default());
+        state.apply(app.world_mut());
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo_steps(2);
+        state.apply(app.world_mut());
+
+        let mut transforms = app.world_mut().query::<&Transform>();
+        assert_eq!(transforms.iter(app.world()).len(), 1);
+
+        let history = app.world().resource::<CommandHistory>();
+        assert!(history.undo_labels().eq(["Spawn"]));
+        assert!(history.redo_labels().eq(["Spawn", "Spawn"]));
+
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.redo_steps(2);
+        state.apply(app.world_mut());
+
+        assert_eq!(transforms.iter(app.world()).len(), 3);
+    }
+
     #[derive(MapEntities)]
     struct Translate {
         #[entities]
@@ -510,6 +1095,12 @@ mod tests {
                 translation: original_translation,
             }))
         }
+
+        fn merge(&mut self, newer: &dyn Any) -> bool {
+            newer
+                .downcast_ref::<Self>()
+                .is_some_and(|newer| newer.entity == self.entity)
+        }
     }
 
     #[derive(MapEntities, Default)]
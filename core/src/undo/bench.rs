@@ -0,0 +1,20 @@
+//! Undo internals exposed for benchmarks.
+
+use bevy::prelude::*;
+
+use super::{EntityRecorder, HistoryCommands, ReversibleCommand};
+
+/// Registers undo without the rest of the game.
+pub fn plugin(app: &mut App) {
+    super::plugin(app);
+}
+
+/// Calls [`HistoryCommands::queue_reversible`].
+pub fn queue_reversible<C: ReversibleCommand>(commands: &mut HistoryCommands, command: C) {
+    commands.queue_reversible(command);
+}
+
+/// Calls [`EntityRecorder::record`].
+pub fn record(recorder: &mut EntityRecorder, entity: Entity) {
+    recorder.record(entity);
+}
@@ -4,7 +4,14 @@ use bevy::{ecs::entity::MapEntities, prelude::*};
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use super::{CommandId, ConfirmableCommand, history::CommandHistory};
+use super::{
+    CommandExpired, CommandId, ConfirmableCommand, history::CommandHistory, revision::Conflict,
+};
+use crate::replay::{ReplayAppExt, ReplayableEvent};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(despawn_expired);
+}
 
 pub(crate) trait ClientCommandAppExt {
     fn add_client_command<C>(&mut self) -> &mut Self
@@ -18,6 +25,7 @@ impl ClientCommandAppExt for App {
         C: ConfirmableCommand + Serialize + DeserializeOwned + MapEntities + Clone,
     {
         self.add_mapped_client_event::<CommandRequest<C>>(Channel::Ordered)
+            .record_client_event::<CommandRequest<C>>()
             .add_server_event::<Confirm<C>>(Channel::Ordered)
             .add_server_event::<Deny<C>>(Channel::Ordered)
             .add_observer(confirm::<C>)
@@ -47,7 +55,17 @@ fn deny<C: ConfirmableCommand>(
     mut history: ResMut<CommandHistory>,
     despawn_entities: Query<(Entity, &DespawnOnResponse)>,
 ) {
-    history.deny(deny.id);
+    if let Some(rollback) = history.deny(deny.id) {
+        commands.queue(rollback);
+    }
+
+    if let Some(conflict) = deny.conflict {
+        info!(
+            "`{:?}` conflicts with changes from `{}`",
+            deny.id, conflict.editor
+        );
+        commands.trigger(conflict);
+    }
 
     for (entity, despawn) in despawn_entities {
         if despawn.id == deny.id {
@@ -57,12 +75,28 @@ fn deny<C: ConfirmableCommand>(
     }
 }
 
+fn despawn_expired(
+    expired: On<CommandExpired>,
+    mut commands: Commands,
+    despawn_entities: Query<(Entity, &DespawnOnResponse)>,
+) {
+    for (entity, despawn) in despawn_entities {
+        if despawn.id == expired.id {
+            debug!("despawning `{entity}` for expired `{:?}`", despawn.id);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
 /// Command request from a client.
 pub(crate) type ClientCommand<C> = FromClient<CommandRequest<C>>;
 
 pub(crate) trait ClientCommandExt<C> {
     fn confirm(&self) -> ToClients<Confirm<C>>;
     fn deny(&self) -> ToClients<Deny<C>>;
+
+    /// Like [`Self::deny`], but reports that the target was changed by another player.
+    fn conflict(&self, conflict: Conflict) -> ToClients<Deny<C>>;
 }
 
 impl<C> ClientCommandExt<C> for ClientCommand<C> {
@@ -81,6 +115,18 @@ impl<C> ClientCommandExt<C> for ClientCommand<C> {
             targets: SendTargets::Single(self.client_id),
             message: Deny {
                 id: self.id,
+                conflict: None,
+                marker: PhantomData,
+            },
+        }
+    }
+
+    fn conflict(&self, conflict: Conflict) -> ToClients<Deny<C>> {
+        ToClients {
+            targets: SendTargets::Single(self.client_id),
+            message: Deny {
+                id: self.id,
+                conflict: Some(conflict),
                 marker: PhantomData,
             },
         }
@@ -94,6 +140,32 @@ pub(crate) struct CommandRequest<C> {
 
     #[deref]
     pub(crate) command: C,
+
+    /// Whether the request is played from a replay instead of sent by a player.
+    ///
+    /// Side effects from [`ConfirmableCommand::apply`], such as placeholders,
+    /// don't exist during replays.
+    #[serde(skip)]
+    pub(crate) replayed: bool,
+}
+
+impl<C> CommandRequest<C> {
+    pub(crate) fn new(id: CommandId, command: C) -> Self {
+        Self {
+            id,
+            command,
+            replayed: false,
+        }
+    }
+}
+
+impl<C> ReplayableEvent for CommandRequest<C>
+where
+    C: Serialize + DeserializeOwned + MapEntities + Send + Sync + 'static,
+{
+    fn mark_replayed(&mut self) {
+        self.replayed = true;
+    }
 }
 
 impl<C: MapEntities> MapEntities for CommandRequest<C> {
@@ -111,6 +183,7 @@ pub(crate) struct Confirm<C> {
 #[derive(Event, Serialize, Deserialize)]
 pub(crate) struct Deny<C> {
     pub(crate) id: CommandId,
+    pub(crate) conflict: Option<Conflict>,
     marker: PhantomData<C>,
 }
 
@@ -1,40 +1,167 @@
-use std::collections::VecDeque;
+use std::{borrow::Cow, collections::VecDeque, mem, time::Duration};
 
-use bevy::{ecs::entity::EntityHashMap, platform::collections::HashMap, prelude::*};
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet, EntityMapper},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use smallvec::SmallVec;
 
-use super::{CommandId, CommandSource, ConfirmableCommand, RecordedEntities, ReversibleCommand};
+use super::{
+    CommandId, CommandSource, ConfirmableCommand, RecordedEntities, ReversibleCommand, Rollback,
+};
+use crate::settings::Settings;
 
+/// Maximum time between commands to merge them into a single record.
+pub(super) const MERGE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Minimum number of aliases before unreferenced ones are searched for.
+const MIN_ALIASES_TO_PRUNE: usize = 64;
+
+/// Stores applied commands to undo and redo them.
+///
+/// Can be inspected to display the history, but modified only via [`HistoryCommands`](super::HistoryCommands).
 #[derive(Resource)]
-pub(crate) struct CommandHistory {
+pub struct CommandHistory {
     undo: VecDeque<CommandRecord>,
     redo: VecDeque<CommandRecord>,
     pending: HashMap<CommandId, PendingCommandRecord>,
+
+    /// Group that currently collects pushed commands.
+    group: Option<CommandGroup>,
+
+    /// Number of nested [`Self::begin_group`] calls.
+    group_depth: usize,
+
+    /// Closed groups that wait for confirmation of their members.
+    pending_groups: Vec<CommandGroup>,
+
+    /// Mappings for entities that were re-created since commands were recorded.
+    aliases: EntityAliases,
+
     max_len: usize,
+    memory_budget: Option<usize>,
 }
 
 impl CommandHistory {
-    pub(super) fn new(max_len: usize) -> Self {
+    /// Creates an empty history that keeps up to `max_len` records in each of the undo and redo stacks.
+    pub fn new(max_len: usize) -> Self {
         Self {
             undo: Default::default(),
             redo: Default::default(),
             pending: Default::default(),
+            group: None,
+            group_depth: 0,
+            pending_groups: Default::default(),
+            aliases: Default::default(),
             max_len,
+            memory_budget: None,
+        }
+    }
+
+    /// Limits approximate memory usage of all stored records.
+    ///
+    /// See [`Self::memory_usage`] for details.
+    pub fn with_memory_budget(mut self, budget: usize) -> Self {
+        self.memory_budget = Some(budget);
+        self
+    }
+
+    /// Applies new limits, dropping the oldest records if they are exceeded.
+    pub(super) fn set_limits(&mut self, max_len: usize, memory_budget: Option<usize>) {
+        self.max_len = max_len;
+        self.memory_budget = memory_budget;
+
+        for stack in [&mut self.undo, &mut self.redo] {
+            while stack.len() > max_len {
+                stack.pop_front();
+            }
         }
+        self.enforce_memory_budget();
     }
 
-    /// Applies queued entity mappings to commands in undo and redo history.
+    /// Returns the approximate memory in bytes used by undo and redo records.
+    ///
+    /// Includes the sizes of the records, their commands and entity aliases.
+    /// Heap allocations inside commands are reported by [`ReversibleCommand::heap_size`]
+    /// and [`ConfirmableCommand::heap_size`].
+    pub fn memory_usage(&self) -> usize {
+        let records: usize = self
+            .undo
+            .iter()
+            .chain(&self.redo)
+            .map(CommandRecord::size)
+            .sum();
+
+        records + self.aliases.size()
+    }
+
+    /// Removes all records, including pending ones.
+    pub(super) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.pending.clear();
+        self.pending_groups.clear();
+        self.aliases.clear();
+    }
+
+    /// Stores queued entity mappings to apply them to commands in history.
+    ///
+    /// Commands are mapped lazily when taken from the history, so this doesn't depend on the history size.
     pub(super) fn flush_entity_mappings(&mut self, queued: &mut EntityHashMap<Entity>) {
         if queued.is_empty() {
             return;
         }
 
-        for record in self.undo.iter_mut().chain(&mut self.redo) {
-            match &mut record.command {
-                HistoryCommand::Reversible(command) => command.dyn_map_entities(queued),
-                HistoryCommand::Confirmable(command) => command.dyn_map_entities(queued),
-            }
+        for (&old_entity, &new_entity) in queued.iter() {
+            self.aliases.insert(old_entity, new_entity);
+        }
+        trace!("queued {} entity mappings", queued.len());
+
+        self.prune_aliases();
+    }
+
+    /// Starts collecting pushed commands into a single record.
+    ///
+    /// Groups can be nested, in which case commands are collected
+    /// until the outermost group is closed.
+    ///
+    /// If `label` is [`None`], it will be generated from labels of the members.
+    pub(super) fn begin_group(&mut self, label: Option<String>, source: CommandSource) {
+        if self.group_depth == 0 {
+            debug!("opening command group");
+            self.group = Some(CommandGroup {
+                members: Default::default(),
+                label,
+                source,
+                sent_at: Duration::ZERO,
+            });
+        }
+        self.group_depth += 1;
+    }
+
+    /// Closes the group opened by [`Self::begin_group`].
+    ///
+    /// The group enters the history once all its members are confirmed.
+    pub(super) fn end_group(&mut self) {
+        let Some(depth) = self.group_depth.checked_sub(1) else {
+            warn!("ignoring closing of a non-existing group");
+            return;
+        };
+        self.group_depth = depth;
+        if depth != 0 {
+            return;
+        }
+
+        let group = self.group.take().expect("group should be open");
+        if group.members.is_empty() {
+            debug!("ignoring empty command group");
+        } else if group.is_confirmed() {
+            self.push_group(group);
+        } else {
+            debug!("waiting for confirmation of the command group");
+            self.pending_groups.push(group);
         }
-        trace!("updated {} entities inside commands", queued.len());
     }
 
     pub(super) fn push_confirmable(
@@ -42,14 +169,32 @@ impl CommandHistory {
         id: CommandId,
         command: Box<dyn ConfirmableCommand>,
         entities: RecordedEntities,
+        label: String,
         source: CommandSource,
+        sent_at: Duration,
     ) {
+        if let Some(group) = self.group_for(source) {
+            group.sent_at = sent_at;
+            group.members.push(GroupMember {
+                pending: Some(id),
+                record: CommandRecord {
+                    command: HistoryCommand::Confirmable(command),
+                    entities,
+                    label,
+                    applied_at: sent_at,
+                },
+            });
+            return;
+        }
+
         self.pending.insert(
             id,
             PendingCommandRecord {
                 command,
                 entities,
+                label,
                 source,
+                sent_at,
             },
         );
     }
@@ -58,56 +203,174 @@ impl CommandHistory {
         &mut self,
         command: Box<dyn ReversibleCommand>,
         entities: RecordedEntities,
+        label: String,
         source: CommandSource,
+        applied_at: Duration,
     ) {
-        self.push(
-            CommandRecord {
-                command: HistoryCommand::Reversible(command),
-                entities,
-            },
-            source,
-        );
+        let record = CommandRecord {
+            command: HistoryCommand::Reversible(command),
+            entities,
+            label,
+            applied_at,
+        };
+
+        if let Some(group) = self.group_for(source) {
+            group.members.push(GroupMember {
+                pending: None,
+                record,
+            });
+            return;
+        }
+
+        self.push(record, source);
     }
 
     /// Confirms a pending [`ConfirmableCommand`].
     ///
     /// Moves the command from the pending state into the undo/redo history.
+    /// If the command is a part of a group, the group is moved only
+    /// after confirmation of all its members.
     pub(crate) fn confirm(&mut self, id: CommandId) {
-        let Some(record) = self.pending.remove(&id) else {
+        if let Some(record) = self.pending.remove(&id) {
+            debug!("confirming `{id:?}`");
+            self.push(
+                CommandRecord {
+                    command: HistoryCommand::Confirmable(record.command),
+                    entities: record.entities,
+                    label: record.label,
+                    applied_at: record.sent_at,
+                },
+                record.source,
+            );
+        } else if let Some(index) = self.pending_group_index(id) {
+            debug!("confirming `{id:?}` inside group");
+            let group = &mut self.pending_groups[index];
+            group.confirm(id);
+            if group.is_confirmed() {
+                let group = self.pending_groups.remove(index);
+                self.push_group(group);
+            }
+        } else if let Some(group) = &mut self.group
+            && group.confirm(id)
+        {
+            debug!("confirming `{id:?}` inside open group");
+        } else {
             debug!("ignoring confirmation for non-existing `{id:?}`");
-            return;
-        };
-
-        debug!("confirming `{id:?}`");
-        self.push(
-            CommandRecord {
-                command: HistoryCommand::Confirmable(record.command),
-                entities: record.entities,
-            },
-            record.source,
-        );
+        }
     }
 
     /// Denies a pending [`ConfirmableCommand`].
     ///
     /// Cancels the command and removes it from the pending state without
     /// adding it to the undo/redo history.
-    pub(crate) fn deny(&mut self, id: CommandId) {
+    ///
+    /// If the command is a part of a group, the whole group is removed and
+    /// the returned [`Rollback`] should be applied to revert confirmed members.
+    /// Members that are still pending are dropped without reverting, since the server
+    /// hasn't applied them yet and responses for them will be ignored.
+    pub(crate) fn deny(&mut self, id: CommandId) -> Option<Rollback> {
         if self.pending.remove(&id).is_some() {
             debug!("denying `{id:?}`");
+            None
+        } else if let Some(index) = self.pending_group_index(id) {
+            // Open groups are closed in the same command flush, so responses can't arrive before it.
+            debug!("denying `{id:?}` and rolling back its group");
+            let group = self.pending_groups.remove(index);
+            let records = group
+                .members
+                .into_iter()
+                .filter(|member| member.pending.is_none())
+                .map(|member| self.map_record(member.record))
+                .collect();
+            self.prune_aliases();
+            Some(Rollback(records))
         } else {
             debug!("ignoring deny for non-existing `{id:?}`");
+            None
+        }
+    }
+
+    /// Returns IDs of pending commands that were sent before `deadline`.
+    ///
+    /// Pending members of the same group are returned together.
+    /// Expired commands should be denied to remove them from the pending state,
+    /// denying any member of a group removes the whole group.
+    pub(super) fn expired(&self, deadline: Duration) -> Vec<SmallVec<[CommandId; 1]>> {
+        let commands = self
+            .pending
+            .iter()
+            .filter(|(_, record)| record.sent_at < deadline)
+            .map(|(&id, _)| SmallVec::from_elem(id, 1));
+
+        let groups = self
+            .pending_groups
+            .iter()
+            .filter(|group| group.sent_at < deadline)
+            .map(|group| {
+                group
+                    .members
+                    .iter()
+                    .filter_map(|member| member.pending)
+                    .collect()
+            });
+
+        commands.chain(groups).collect()
+    }
+
+    /// Removes all pending commands and groups without adding them to the history.
+    ///
+    /// Returns IDs of removed commands.
+    pub(super) fn clear_pending(&mut self) -> Vec<CommandId> {
+        let commands = self.pending.drain().map(|(id, _)| id);
+        let group_members = self
+            .pending_groups
+            .drain(..)
+            .flat_map(|group| group.members)
+            .filter_map(|member| member.pending);
+
+        commands.chain(group_members).collect()
+    }
+
+    /// Returns the open group if commands from this source should be collected into it.
+    fn group_for(&mut self, source: CommandSource) -> Option<&mut CommandGroup> {
+        if source == CommandSource::Rollback {
+            return None;
         }
+
+        self.group.as_mut()
+    }
+
+    fn pending_group_index(&self, id: CommandId) -> Option<usize> {
+        self.pending_groups
+            .iter()
+            .position(|group| group.members.iter().any(|m| m.pending == Some(id)))
+    }
+
+    fn push_group(&mut self, group: CommandGroup) {
+        let label = group.label.unwrap_or_else(|| group_label(&group.members));
+        let records = group.members.into_iter().map(|m| m.record).collect();
+        self.push(
+            CommandRecord {
+                command: HistoryCommand::Group(records),
+                entities: Default::default(),
+                label,
+                applied_at: group.sent_at,
+            },
+            group.source,
+        );
     }
 
     fn push(&mut self, record: CommandRecord, source: CommandSource) {
-        let name = match &record.command {
-            HistoryCommand::Reversible(command) => command.dyn_name(),
-            HistoryCommand::Confirmable(command) => command.dyn_name(),
-        };
+        let name = &record.label;
 
         match source {
             CommandSource::User => {
+                if self.merge_last(&record) {
+                    debug!("merging `{name}` into the last undo");
+                    self.redo.clear();
+                    return;
+                }
+
                 debug!("adding `{name}` to undo and clearing redo");
                 self.redo.clear();
                 push_to_stack(&mut self.undo, record, self.max_len);
@@ -120,21 +383,296 @@ impl CommandHistory {
                 debug!("adding `{name}` to undo");
                 push_to_stack(&mut self.undo, record, self.max_len);
             }
+            CommandSource::Rollback => {
+                debug!("dropping `{name}` from rollback");
+            }
+        }
+
+        self.enforce_memory_budget();
+    }
+
+    /// Drops the oldest records until the usage fits into the budget.
+    ///
+    /// Undo records are dropped first, then the farthest redo records.
+    /// The last remaining record is always kept.
+    fn enforce_memory_budget(&mut self) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+
+        let mut usage = self.memory_usage();
+        if usage <= budget {
+            return;
+        }
+
+        // Some aliases could be referenced only by already dropped records.
+        self.retain_referenced_aliases();
+        usage = self.memory_usage();
+
+        let mut dropped = false;
+        while usage > budget && self.undo.len() + self.redo.len() > 1 {
+            let record = if self.undo.is_empty() {
+                self.redo.pop_front()
+            } else {
+                self.undo.pop_front()
+            };
+            let record = record.expect("stacks shouldn't be empty");
+            debug!("dropping `{}` to fit into memory budget", record.label);
+            usage -= record.size();
+            dropped = true;
+        }
+
+        if dropped {
+            self.retain_referenced_aliases();
+        }
+    }
+
+    /// Tries to merge a new record into the most recent undo record.
+    ///
+    /// See [`ReversibleCommand::merge`] for details.
+    fn merge_last(&mut self, record: &CommandRecord) -> bool {
+        let Some(last) = self.undo.back_mut() else {
+            return false;
+        };
+
+        // Referenced entities need to be up to date for comparison.
+        if !self.aliases.is_empty() {
+            last.command.map_entities(&mut self.aliases.map);
+        }
+
+        if record.applied_at.saturating_sub(last.applied_at) > MERGE_WINDOW {
+            return false;
+        }
+
+        let merged = match (&mut last.command, &record.command) {
+            (HistoryCommand::Reversible(last), HistoryCommand::Reversible(newer)) => {
+                last.merge((**newer).as_any())
+            }
+            (HistoryCommand::Confirmable(last), HistoryCommand::Confirmable(newer)) => {
+                last.merge((**newer).as_any())
+            }
+            _ => false,
+        };
+
+        if merged {
+            // Extend the window to keep merging continuous edits.
+            last.applied_at = record.applied_at;
         }
+
+        merged
+    }
+
+    /// Returns labels of commands that can be undone, from the oldest to the most recent.
+    pub fn undo_labels(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.undo.iter().map(|record| record.label.as_str())
+    }
+
+    /// Returns labels of commands that can be redone, from the next one to the last.
+    pub fn redo_labels(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.redo.iter().rev().map(|record| record.label.as_str())
+    }
+
+    /// Returns labels of commands that wait for confirmation from the server, in order of sending.
+    ///
+    /// They will be moved into undo or redo history after confirmation.
+    pub fn pending_labels(&self) -> Vec<Cow<'_, str>> {
+        let mut labels: Vec<_> = self
+            .pending
+            .iter()
+            .map(|(&id, record)| (id, Cow::Borrowed(record.label.as_str())))
+            .chain(self.pending_groups.iter().filter_map(|group| {
+                let id = group.members.iter().find_map(|m| m.pending)?;
+                let label = match &group.label {
+                    Some(label) => Cow::Borrowed(label.as_str()),
+                    None => Cow::Owned(group_label(&group.members)),
+                };
+                Some((id, label))
+            }))
+            .collect();
+        labels.sort_by_key(|&(id, _)| id);
+
+        labels.into_iter().map(|(_, label)| label).collect()
     }
 
     pub(super) fn pop_undo(&mut self) -> Option<CommandRecord> {
-        self.undo.pop_back()
+        let record = self.undo.pop_back()?;
+        let record = self.map_record(record);
+        self.prune_aliases();
+        Some(record)
     }
 
     pub(super) fn pop_redo(&mut self) -> Option<CommandRecord> {
-        self.redo.pop_back()
+        let record = self.redo.pop_back()?;
+        let record = self.map_record(record);
+        self.prune_aliases();
+        Some(record)
+    }
+
+    /// Updates entity references inside the record to the latest entities.
+    fn map_record(&mut self, mut record: CommandRecord) -> CommandRecord {
+        if !self.aliases.is_empty() {
+            record.command.map_entities(&mut self.aliases.map);
+        }
+        record
+    }
+
+    /// Removes aliases that no record could reference.
+    ///
+    /// Searching for referenced entities walks all records, so it's done only
+    /// when the number of aliases doubles since the last search.
+    fn prune_aliases(&mut self) {
+        if self.undo.is_empty()
+            && self.redo.is_empty()
+            && self.pending.is_empty()
+            && self.pending_groups.is_empty()
+            && self.group.is_none()
+        {
+            self.aliases.clear();
+        } else if self.aliases.len() >= self.aliases.prune_threshold {
+            self.retain_referenced_aliases();
+        }
+    }
+
+    /// Removes aliases for entities that aren't referenced by any record.
+    fn retain_referenced_aliases(&mut self) {
+        if self.aliases.is_empty() {
+            return;
+        }
+
+        let mut collector = EntityCollector::default();
+        let records = self.undo.iter_mut().chain(&mut self.redo).chain(
+            self.pending_groups
+                .iter_mut()
+                .chain(&mut self.group)
+                .flat_map(|group| &mut group.members)
+                .map(|member| &mut member.record),
+        );
+        for record in records {
+            record.collect_entities(&mut collector);
+        }
+        for record in self.pending.values_mut() {
+            collector.entities.extend(record.entities.iter().copied());
+            record.command.dyn_map_entities(&mut collector);
+        }
+
+        let len = self.aliases.len();
+        self.aliases.retain(&collector.entities);
+        debug!("pruned {} unreferenced aliases", len - self.aliases.len());
     }
 }
 
-impl Default for CommandHistory {
+impl FromWorld for CommandHistory {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world
+            .get_resource::<Settings>()
+            .map(|settings| settings.undo)
+            .unwrap_or_default();
+
+        let history = Self::new(settings.max_len);
+        match settings.memory_budget {
+            Some(budget) => history.with_memory_budget(budget),
+            None => history,
+        }
+    }
+}
+
+/// Maps entities that were despawned and re-created by commands to their latest versions.
+///
+/// Chains are collapsed on insertion, so each entity is mapped with a single lookup.
+struct EntityAliases {
+    map: EntityHashMap<Entity>,
+
+    /// Reverse index for [`Self::map`] to update chains.
+    sources: EntityHashMap<SmallVec<[Entity; 1]>>,
+
+    /// Number of aliases at which unreferenced ones should be searched for.
+    prune_threshold: usize,
+}
+
+impl Default for EntityAliases {
     fn default() -> Self {
-        Self::new(25)
+        Self {
+            map: Default::default(),
+            sources: Default::default(),
+            prune_threshold: MIN_ALIASES_TO_PRUNE,
+        }
+    }
+}
+
+impl EntityAliases {
+    fn insert(&mut self, old_entity: Entity, new_entity: Entity) {
+        let mut sources = self.sources.remove(&old_entity).unwrap_or_default();
+        sources.push(old_entity);
+        for &source in &sources {
+            self.map.insert(source, new_entity);
+        }
+        self.sources.entry(new_entity).or_default().extend(sources);
+    }
+
+    /// Keeps only aliases for the specified old entities.
+    fn retain(&mut self, entities: &EntityHashSet) {
+        self.map.retain(|entity, _| entities.contains(entity));
+        self.sources.retain(|_, sources| {
+            sources.retain(|entity| entities.contains(entity));
+            !sources.is_empty()
+        });
+        self.prune_threshold = (self.map.len() * 2).max(MIN_ALIASES_TO_PRUNE);
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.sources.clear();
+        self.prune_threshold = MIN_ALIASES_TO_PRUNE;
+    }
+
+    /// Returns the approximate memory used by the aliases.
+    fn size(&self) -> usize {
+        let map = self.map.capacity() * mem::size_of::<(Entity, Entity)>();
+        let sources = self.sources.capacity() * mem::size_of::<(Entity, SmallVec<[Entity; 1]>)>();
+        let spilled: usize = self
+            .sources
+            .values()
+            .filter(|sources| sources.spilled())
+            .map(|sources| sources.capacity() * mem::size_of::<Entity>())
+            .sum();
+
+        map + sources + spilled
+    }
+}
+
+/// Collects all entities referenced by commands.
+#[derive(Default)]
+struct EntityCollector {
+    entities: EntityHashSet,
+}
+
+impl EntityMapper for EntityCollector {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.entities.insert(source);
+        source
+    }
+
+    fn set_mapped(&mut self, _source: Entity, _target: Entity) {}
+}
+
+/// Generates label for a group without explicitly specified one.
+fn group_label(members: &[GroupMember]) -> String {
+    let Some((first, others)) = members.split_first() else {
+        return String::new();
+    };
+
+    if others.is_empty() {
+        first.record.label.clone()
+    } else {
+        format!("{} and {} more", first.record.label, others.len())
     }
 }
 
@@ -148,7 +686,11 @@ fn push_to_stack(stack: &mut VecDeque<CommandRecord>, record: CommandRecord, max
 struct PendingCommandRecord {
     command: Box<dyn ConfirmableCommand>,
     entities: RecordedEntities,
+    label: String,
     source: CommandSource,
+
+    /// Real time when the command was sent.
+    sent_at: Duration,
 }
 
 /// A command stored in the undo or redo history.
@@ -160,9 +702,189 @@ pub(super) struct CommandRecord {
     /// Used to update entity references in other commands once [`Self::command`]
     /// executes and produces new entities.
     pub(super) entities: RecordedEntities,
+
+    /// Description of the action that the inverted [`Self::command`] reverses.
+    ///
+    /// Preserved between undo and redo.
+    pub(super) label: String,
+
+    /// Real time when the command was applied.
+    pub(super) applied_at: Duration,
+}
+
+impl CommandRecord {
+    /// Adds all entities referenced by the record to the collector.
+    fn collect_entities(&mut self, collector: &mut EntityCollector) {
+        collector.entities.extend(self.entities.iter().copied());
+        match &mut self.command {
+            HistoryCommand::Group(records) => {
+                for record in records {
+                    record.collect_entities(collector);
+                }
+            }
+            command => command.map_entities(collector),
+        }
+    }
+
+    /// Returns the approximate memory used by the record.
+    fn size(&self) -> usize {
+        let command = match &self.command {
+            HistoryCommand::Reversible(command) => {
+                mem::size_of_val(&**command) + command.heap_size()
+            }
+            HistoryCommand::Confirmable(command) => {
+                mem::size_of_val(&**command) + command.heap_size()
+            }
+            HistoryCommand::Group(records) => records.iter().map(Self::size).sum(),
+        };
+        let entities = if self.entities.spilled() {
+            self.entities.capacity() * mem::size_of::<Entity>()
+        } else {
+            0
+        };
+
+        mem::size_of::<Self>() + command + entities + self.label.capacity()
+    }
 }
 
 pub(super) enum HistoryCommand {
     Reversible(Box<dyn ReversibleCommand>),
     Confirmable(Box<dyn ConfirmableCommand>),
+
+    /// Commands that undo and redo together, stored in the order of application.
+    Group(Vec<CommandRecord>),
+}
+
+impl HistoryCommand {
+    fn map_entities(&mut self, mapper: &mut dyn EntityMapper) {
+        match self {
+            HistoryCommand::Reversible(command) => command.dyn_map_entities(mapper),
+            HistoryCommand::Confirmable(command) => command.dyn_map_entities(mapper),
+            HistoryCommand::Group(records) => {
+                for record in records {
+                    record.command.map_entities(mapper);
+                }
+            }
+        }
+    }
+}
+
+/// Commands collected between [`CommandHistory::begin_group`] and [`CommandHistory::end_group`].
+struct CommandGroup {
+    members: Vec<GroupMember>,
+    label: Option<String>,
+    source: CommandSource,
+
+    /// Real time when the last confirmable member was sent.
+    ///
+    /// All members are applied within a single command flush, so there is no need to track it per member.
+    sent_at: Duration,
+}
+
+impl CommandGroup {
+    /// Marks the member as confirmed and returns `true` if it was found.
+    fn confirm(&mut self, id: CommandId) -> bool {
+        let Some(member) = self.members.iter_mut().find(|m| m.pending == Some(id)) else {
+            return false;
+        };
+
+        member.pending = None;
+        true
+    }
+
+    fn is_confirmed(&self) -> bool {
+        self.members.iter().all(|m| m.pending.is_none())
+    }
+}
+
+struct GroupMember {
+    /// ID of the command if it still waits for confirmation.
+    pending: Option<CommandId>,
+    record: CommandRecord,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::entity::MapEntities;
+    use test_log::test;
+
+    use super::*;
+    use crate::undo::EntityRecorder;
+
+    #[test]
+    fn prune_aliases() {
+        let mut world = World::new();
+        let mut history = CommandHistory::new(1);
+        for _ in 0..MIN_ALIASES_TO_PRUNE {
+            let old_entity = world.spawn_empty().id();
+            let new_entity = world.spawn_empty().id();
+            history.push_reversible(
+                Box::new(Touch { entity: old_entity }),
+                Default::default(),
+                Default::default(),
+                CommandSource::User,
+                Duration::ZERO,
+            );
+
+            let mut queued = EntityHashMap::default();
+            queued.insert(old_entity, new_entity);
+            history.flush_entity_mappings(&mut queued);
+        }
+
+        assert_eq!(
+            history.aliases.len(),
+            1,
+            "only the alias referenced by the last record should be kept"
+        );
+    }
+
+    #[test]
+    fn heap_size() {
+        let mut history = CommandHistory::new(1);
+        history.push_reversible(
+            Box::new(Payload(vec![0; 1024])),
+            Default::default(),
+            Default::default(),
+            CommandSource::User,
+            Duration::ZERO,
+        );
+
+        assert!(
+            history.memory_usage() > 1024,
+            "heap allocations of commands should be counted"
+        );
+    }
+
+    #[derive(MapEntities)]
+    struct Touch {
+        #[entities]
+        entity: Entity,
+    }
+
+    impl ReversibleCommand for Touch {
+        fn apply(
+            self: Box<Self>,
+            _recorder: &mut EntityRecorder,
+            _world: &mut World,
+        ) -> Option<Box<dyn ReversibleCommand>> {
+            None
+        }
+    }
+
+    #[derive(MapEntities)]
+    struct Payload(Vec<u8>);
+
+    impl ReversibleCommand for Payload {
+        fn apply(
+            self: Box<Self>,
+            _recorder: &mut EntityRecorder,
+            _world: &mut World,
+        ) -> Option<Box<dyn ReversibleCommand>> {
+            None
+        }
+
+        fn heap_size(&self) -> usize {
+            self.0.capacity()
+        }
+    }
 }
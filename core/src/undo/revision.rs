@@ -0,0 +1,139 @@
+use bevy::{ecs::entity::EntityHashMap, platform::collections::HashMap, prelude::*};
+use bevy_replicon::{
+    prelude::*,
+    shared::backend::connected_client::{ConnectedClient, NetworkId},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::network::PlayerId;
+
+pub(super) fn plugin(app: &mut App) {
+    app.replicate::<Revision>()
+        .init_resource::<PlayerHistories>()
+        .add_observer(forget_entity)
+        .add_observer(forget_player);
+}
+
+fn forget_entity(remove: On<Remove, Revision>, mut histories: ResMut<PlayerHistories>) {
+    for changes in histories.0.values_mut() {
+        changes.remove(&remove.entity);
+    }
+}
+
+/// Drops the history of a disconnected client since its commands can't arrive anymore.
+fn forget_player(
+    remove: On<Remove, ConnectedClient>,
+    mut histories: ResMut<PlayerHistories>,
+    clients: Query<&NetworkId>,
+) {
+    if let Ok(network_id) = clients.get(remove.entity) {
+        let player = PlayerId::Client(network_id.get());
+        debug!("forgetting history of `{player}`");
+        histories.0.remove(&player);
+    }
+}
+
+/// Tracks changes of an entity made by confirmable commands on the server.
+#[derive(Component, Serialize, Deserialize, Default, Debug, Clone, Copy)]
+pub struct Revision {
+    version: u32,
+    editor: PlayerId,
+}
+
+impl Revision {
+    pub(crate) fn new(editor: PlayerId) -> Self {
+        Self { version: 0, editor }
+    }
+}
+
+/// Changes made by each player, tracked on the server.
+///
+/// Commands from the history of a player were created for the state after
+/// the last change of this player. If another player changed the entity since
+/// then, applying such command would silently overwrite their changes.
+#[derive(Resource, Default)]
+pub(crate) struct PlayerHistories(HashMap<PlayerId, EntityHashMap<u32>>);
+
+impl PlayerHistories {
+    /// Records a newly spawned entity.
+    pub(crate) fn spawn(&mut self, entity: Entity, revision: Revision) {
+        self.record(entity, revision);
+    }
+
+    /// Bumps the revision and records the change made by the given player.
+    pub(crate) fn change(&mut self, entity: Entity, revision: &mut Revision, editor: PlayerId) {
+        revision.version += 1;
+        revision.editor = editor;
+        self.record(entity, *revision);
+    }
+
+    /// Returns an error if the entity was changed by another player
+    /// since the last change made by the given player.
+    pub(crate) fn check(
+        &self,
+        entity: Entity,
+        revision: &Revision,
+        player: PlayerId,
+    ) -> Result<(), Conflict> {
+        let last_version = self.0.get(&player).and_then(|changes| changes.get(&entity));
+
+        if last_version == Some(&revision.version) {
+            Ok(())
+        } else {
+            Err(Conflict {
+                editor: revision.editor,
+            })
+        }
+    }
+
+    fn record(&mut self, entity: Entity, revision: Revision) {
+        self.0
+            .entry(revision.editor)
+            .or_default()
+            .insert(entity, revision.version);
+    }
+}
+
+/// Reason for denying a command from the history.
+///
+/// Triggered on the client that sent the command.
+#[derive(Event, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Conflict {
+    /// The player who made the last change.
+    pub editor: PlayerId,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn check() {
+        let mut histories = PlayerHistories::default();
+        let entity = Entity::PLACEHOLDER;
+        let first = PlayerId::Client(1);
+        let second = PlayerId::Client(2);
+
+        let mut revision = Revision::new(first);
+        histories.spawn(entity, revision);
+        assert!(histories.check(entity, &revision, first).is_ok());
+        assert!(
+            histories.check(entity, &revision, second).is_err(),
+            "player without changes shouldn't edit from history"
+        );
+
+        histories.change(entity, &mut revision, first);
+        histories.change(entity, &mut revision, first);
+        assert!(
+            histories.check(entity, &revision, first).is_ok(),
+            "own changes shouldn't conflict"
+        );
+
+        histories.change(entity, &mut revision, second);
+        let conflict = histories.check(entity, &revision, first).unwrap_err();
+        assert_eq!(conflict.editor, second);
+        assert!(histories.check(entity, &revision, second).is_ok());
+    }
+}
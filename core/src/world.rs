@@ -1,8 +1,10 @@
+pub mod attachment;
 pub mod character;
 mod city;
 mod combined_collider;
 pub mod cursor;
 pub mod family;
+pub mod funds;
 mod layer;
 pub mod object;
 mod placing;
@@ -21,11 +23,13 @@ use crate::{error_event::trigger_error, game_paths::GamePaths, state::GameState}
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        attachment::plugin,
         character::plugin,
         city::plugin,
         combined_collider::plugin,
         cursor::plugin,
         family::plugin,
+        funds::plugin,
         object::plugin,
         placing::plugin,
         player_camera::plugin,
@@ -81,19 +85,31 @@ fn load(
     info!("loading {path:?}");
 
     let bytes = fs::read(&path).map_err(|e| format!("unable to load {path:?}: {e}"))?;
-    let mut deserializer = ron::Deserializer::from_bytes(&bytes)
-        .map_err(|e| format!("unable to parse {path:?}: {e}"))?;
+    let dyn_world = deserialize(&bytes, &registry, &asset_server)
+        .map_err(|e| format!("unable to load {path:?}: {e}"))?;
+
+    instance_spawner.spawn_dynamic(dyn_worlds.add(dyn_world));
+
+    Ok(())
+}
+
+/// Deserializes a world previously saved with [`SaveWorld`].
+pub(crate) fn deserialize(
+    bytes: &[u8],
+    registry: &AppTypeRegistry,
+    asset_server: &AssetServer,
+) -> Result<DynamicWorld> {
+    let mut deserializer =
+        ron::Deserializer::from_bytes(bytes).map_err(|e| format!("unable to parse: {e}"))?;
     let world_deserializer = WorldDeserializer {
         type_registry: &registry.read(),
         load_from_path: &mut asset_server.clone(),
     };
     let dyn_world = world_deserializer
         .deserialize(&mut deserializer)
-        .map_err(|e| format!("unable to deserialize {path:?}: {e}"))?;
-
-    instance_spawner.spawn_dynamic(dyn_worlds.add(dyn_world));
+        .map_err(|e| format!("unable to deserialize: {e}"))?;
 
-    Ok(())
+    Ok(dyn_world)
 }
 
 fn update_state(_on: On<Add, WorldName>, mut commands: Commands, world_name: Single<&WorldName>) {
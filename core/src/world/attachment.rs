@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+
+use bevy::{asset::AssetPath, prelude::*};
+
+use crate::asset_manifest::{ReflectResolvePaths, ResolvePaths};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(spawn_asset)
+        .add_observer(spawn_picture)
+        .add_observer(despawn::<AttachedAsset>)
+        .add_observer(despawn::<Picture>);
+}
+
+fn spawn_asset(
+    insert: On<Insert, AttachedAsset>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    attached_assets: Query<&AttachedAsset>,
+) {
+    let attached = attached_assets.get(insert.entity).unwrap();
+
+    debug!("attaching '{}' to `{}`", attached.asset, insert.entity);
+    commands.spawn((
+        Attachment::<AttachedAsset>::default(),
+        WorldAssetRoot(asset_server.load(attached.asset.clone())),
+        attached.transform,
+        ChildOf(insert.entity),
+    ));
+}
+
+fn spawn_picture(
+    insert: On<Insert, Picture>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    pictures: Query<&Picture>,
+) {
+    let picture = pictures.get(insert.entity).unwrap();
+
+    debug!("attaching '{}' to `{}`", picture.texture, insert.entity);
+    commands.spawn((
+        Attachment::<Picture>::default(),
+        Mesh3d(meshes.add(Rectangle::from_size(picture.size))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(asset_server.load(picture.texture.clone())),
+            ..Default::default()
+        })),
+        picture.transform,
+        ChildOf(insert.entity),
+    ));
+}
+
+/// Despawns the spawned entity when the component is replaced or removed.
+fn despawn<C: Component>(
+    replace: On<Replace, C>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    attachments: Query<Entity, With<Attachment<C>>>,
+) {
+    let Ok(children) = children.get(replace.entity) else {
+        return;
+    };
+
+    for attachment in attachments.iter_many(children) {
+        debug!("detaching `{attachment}` from `{}`", replace.entity);
+        // The parent could be despawned together with its children.
+        commands.entity(attachment).try_despawn();
+    }
+}
+
+/// Additional scene spawned as a child, such as a secondary mesh.
+///
+/// Useful to reuse the same asset across multiple manifests.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, ResolvePaths)]
+pub struct AttachedAsset {
+    pub asset: AssetPath<'static>,
+
+    /// Offset relative to the entity.
+    #[reflect(default)]
+    pub transform: Transform,
+}
+
+impl ResolvePaths for AttachedAsset {
+    fn resolve_paths(&mut self, manifest_path: &AssetPath) {
+        self.asset = manifest_path.resolve_embed(&self.asset);
+    }
+
+    fn asset_paths(&self) -> Vec<&AssetPath<'static>> {
+        vec![&self.asset]
+    }
+}
+
+/// Texture displayed on a rectangle facing +Z, such as a painting or a poster.
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, ResolvePaths)]
+pub struct Picture {
+    pub texture: AssetPath<'static>,
+
+    /// Width and height in meters.
+    pub size: Vec2,
+
+    /// Offset relative to the entity.
+    #[reflect(default)]
+    pub transform: Transform,
+}
+
+impl ResolvePaths for Picture {
+    fn resolve_paths(&mut self, manifest_path: &AssetPath) {
+        self.texture = manifest_path.resolve_embed(&self.texture);
+    }
+
+    fn asset_paths(&self) -> Vec<&AssetPath<'static>> {
+        vec![&self.texture]
+    }
+}
+
+/// Entity spawned for component `C` of its parent.
+#[derive(Component)]
+struct Attachment<C>(PhantomData<C>);
+
+impl<C> Default for Attachment<C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn replace_asset() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), plugin));
+
+        let entity = app
+            .world_mut()
+            .spawn(AttachedAsset {
+                asset: "lamp.glb".into(),
+                transform: Default::default(),
+            })
+            .id();
+        app.update();
+        assert_eq!(attachments::<AttachedAsset>(&mut app, entity), 1);
+
+        app.world_mut().entity_mut(entity).insert(AttachedAsset {
+            asset: "shade.glb".into(),
+            transform: Default::default(),
+        });
+        app.update();
+        assert_eq!(
+            attachments::<AttachedAsset>(&mut app, entity),
+            1,
+            "previous attachment should be replaced"
+        );
+
+        app.world_mut().entity_mut(entity).remove::<AttachedAsset>();
+        app.update();
+        assert_eq!(attachments::<AttachedAsset>(&mut app, entity), 0);
+    }
+
+    #[test]
+    fn replace_picture() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), plugin))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>();
+
+        let entity = app
+            .world_mut()
+            .spawn(Picture {
+                texture: "painting.png".into(),
+                size: Vec2::ONE,
+                transform: Default::default(),
+            })
+            .id();
+        app.update();
+        assert_eq!(attachments::<Picture>(&mut app, entity), 1);
+
+        app.world_mut().entity_mut(entity).insert(Picture {
+            texture: "poster.png".into(),
+            size: Vec2::splat(2.0),
+            transform: Default::default(),
+        });
+        app.update();
+        assert_eq!(
+            attachments::<Picture>(&mut app, entity),
+            1,
+            "previous attachment should be replaced"
+        );
+
+        app.world_mut().entity_mut(entity).remove::<Picture>();
+        app.update();
+        assert_eq!(attachments::<Picture>(&mut app, entity), 0);
+    }
+
+    fn attachments<C: Component>(app: &mut App, parent: Entity) -> usize {
+        let mut attachments = app
+            .world_mut()
+            .query_filtered::<&ChildOf, With<Attachment<C>>>();
+        attachments
+            .iter(app.world())
+            .filter(|child_of| child_of.parent() == parent)
+            .count()
+    }
+}
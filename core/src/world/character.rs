@@ -1,12 +1,16 @@
+pub mod appearance;
+
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy_replicon::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::world::layer::GameLayer;
+use appearance::Appearance;
 
 pub(super) fn plugin(app: &mut App) {
-    app.replicate::<FirstName>()
+    app.add_plugins(appearance::plugin)
+        .replicate::<FirstName>()
         .replicate::<LastName>()
         .replicate::<Sex>();
 }
@@ -20,7 +24,9 @@ const RADIUS: f32 = 0.4;
     FirstName,
     LastName,
     Replicated,
-    WorldAssetRoot,
+    Appearance,
+    Transform,
+    Visibility,
     RigidBody::Kinematic,
     Collider::capsule_endpoints(
         RADIUS,
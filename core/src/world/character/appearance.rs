@@ -0,0 +1,261 @@
+use bevy::{asset::AssetPath, ecs::reflect::ReflectCommandExt, prelude::*};
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_manifest::{
+        ManifestPaths,
+        appearance::{AppearanceManifest, AppearanceSlot},
+    },
+    world::character::Sex,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.replicate::<Appearance>()
+        .add_observer(init)
+        .add_systems(Update, reload);
+}
+
+/// Spawns a part for each manifest, replacing the previous ones.
+///
+/// Characters without a body use the first body that fits their sex.
+fn init(
+    insert: On<Insert, Appearance>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    manifests: Res<Assets<AppearanceManifest>>,
+    appearances: Query<(&Appearance, &Sex, Option<&Children>)>,
+    parts: Query<Entity, With<AppearancePart>>,
+) {
+    let (appearance, &sex, children) = appearances.get(insert.entity).unwrap();
+
+    if let Some(children) = children {
+        for part in parts.iter_many(children) {
+            commands.entity(part).despawn();
+        }
+    }
+
+    let mut slots: Vec<_> = appearance
+        .iter()
+        .filter_map(|(slot, path)| {
+            let Some(manifest) = manifest_paths
+                .get_handle(&asset_server, path)
+                .and_then(|handle| manifests.get(&handle))
+            else {
+                error!("'{path}' is missing, ignoring");
+                return None;
+            };
+
+            if !manifest.fits(slot, sex) {
+                warn!("'{path}' is used as `{slot:?}` for `{sex:?}`, but doesn't fit");
+            }
+
+            Some((path.clone(), manifest))
+        })
+        .collect();
+
+    if appearance.body.is_none() {
+        match default_body(&asset_server, &manifests, sex) {
+            Some(body) => slots.push(body),
+            None => warn!("no body fits `{sex:?}`"),
+        }
+    }
+
+    for (path, manifest) in slots {
+        debug!("spawning '{path}' for `{}`", insert.entity);
+        let mut part = commands.spawn((
+            AppearancePart,
+            Name::new(manifest.info.name.to_string()),
+            WorldAssetRoot(asset_server.load(manifest.asset.clone())),
+            ChildOf(insert.entity),
+        ));
+        for component in &manifest.components {
+            part.insert_reflect(component.as_ref().reflect_clone().unwrap());
+        }
+    }
+}
+
+/// Returns the body with the lowest path that fits the sex.
+///
+/// Sorted by path to pick the same body on all machines.
+fn default_body<'a>(
+    asset_server: &AssetServer,
+    manifests: &'a Assets<AppearanceManifest>,
+    sex: Sex,
+) -> Option<(AssetPath<'static>, &'a AppearanceManifest)> {
+    manifests
+        .iter()
+        .filter(|(_, manifest)| manifest.fits(AppearanceSlot::Body, sex))
+        .filter_map(|(id, manifest)| {
+            let path = asset_server.get_path(id)?;
+            Some((ManifestPaths::canonical(&path), manifest))
+        })
+        .min_by_key(|(path, _)| path.to_string())
+}
+
+/// Respawns parts for characters that use manifests modified on disk.
+fn reload(
+    mut commands: Commands,
+    mut asset_events: MessageReader<AssetEvent<AppearanceManifest>>,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    appearances: Query<(Entity, &Appearance)>,
+) {
+    for &event in asset_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        for (entity, appearance) in &appearances {
+            // Characters without a body may use the modified manifest as the default one.
+            if appearance.body.is_none()
+                || appearance.iter().any(|(_, path)| {
+                    manifest_paths
+                        .get_handle::<AppearanceManifest>(&asset_server, path)
+                        .is_some_and(|handle| handle.id() == id)
+                })
+            {
+                debug!("reloading appearance for `{entity}`");
+                commands.entity(entity).insert(appearance.clone());
+            }
+        }
+    }
+}
+
+/// Paths to [`AppearanceManifest`]s for each slot.
+///
+/// Paths are stored without a source, see [`ManifestPaths`].
+/// If [`Self::body`] is not set, the first body that fits the character is used.
+#[derive(Component, Reflect, Serialize, Deserialize, Default, Clone)]
+#[component(immutable)]
+#[reflect(Component)]
+pub struct Appearance {
+    pub body: Option<AssetPath<'static>>,
+    pub hair: Option<AssetPath<'static>>,
+    pub outfit: Option<AssetPath<'static>>,
+}
+
+impl Appearance {
+    /// Iterates over assigned slots.
+    pub fn iter(&self) -> impl Iterator<Item = (AppearanceSlot, &AssetPath<'static>)> {
+        [
+            (AppearanceSlot::Body, &self.body),
+            (AppearanceSlot::Hair, &self.hair),
+            (AppearanceSlot::Outfit, &self.outfit),
+        ]
+        .into_iter()
+        .filter_map(|(slot, path)| path.as_ref().map(|path| (slot, path)))
+    }
+}
+
+/// Spawned asset from a single [`AppearanceManifest`].
+#[derive(Component)]
+struct AppearancePart;
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+    use crate::test_session::TestSession;
+
+    #[test]
+    fn respawn() {
+        let mut session = TestSession::new(0, setup);
+        let app = &mut session.server;
+        let character = app
+            .world_mut()
+            .spawn((
+                Sex::Female,
+                Appearance {
+                    body: Some(BODY.into()),
+                    ..Default::default()
+                },
+            ))
+            .id();
+        app.update();
+        assert_eq!(parts(app, character), ["Body"]);
+
+        app.world_mut().entity_mut(character).insert(Appearance {
+            body: Some(BODY.into()),
+            outfit: Some(OUTFIT.into()),
+            ..Default::default()
+        });
+        app.update();
+        assert_eq!(
+            parts(app, character),
+            ["Body", "Outfit"],
+            "previous parts should be replaced"
+        );
+    }
+
+    #[test]
+    fn default_body() {
+        let mut session = TestSession::new(0, setup);
+        let app = &mut session.server;
+        let female = app
+            .world_mut()
+            .spawn((Sex::Female, Appearance::default()))
+            .id();
+        let male = app
+            .world_mut()
+            .spawn((Sex::Male, Appearance::default()))
+            .id();
+        app.update();
+
+        assert_eq!(parts(app, female), ["Body"]);
+        assert!(parts(app, male).is_empty(), "no body fits");
+    }
+
+    fn setup(app: &mut App) {
+        app.init_asset::<AppearanceManifest>()
+            .init_resource::<ManifestPaths>()
+            .add_plugins(plugin);
+
+        let mut handles = Vec::new();
+        for (path, name, slot) in [
+            (BODY, "Body", AppearanceSlot::Body),
+            (OUTFIT, "Outfit", AppearanceSlot::Outfit),
+        ] {
+            let handle = app
+                .world()
+                .resource::<AssetServer>()
+                .get_or_create_path_handle::<AppearanceManifest>(path, None);
+            let mut manifest = AppearanceManifest {
+                slot,
+                sexes: vec![Sex::Female],
+                asset: "part.glb".into(),
+                ..Default::default()
+            };
+            manifest.info.name = name.into();
+            app.world_mut()
+                .resource_mut::<Assets<AppearanceManifest>>()
+                .insert(&handle, manifest)
+                .unwrap();
+            handles.push(handle);
+        }
+        app.insert_resource(TestManifests(handles));
+    }
+
+    /// Returns sorted names of spawned parts.
+    fn parts(app: &mut App, character: Entity) -> Vec<String> {
+        let mut parts = app
+            .world_mut()
+            .query_filtered::<(&Name, &ChildOf), With<AppearancePart>>();
+        let mut names: Vec<_> = parts
+            .iter(app.world())
+            .filter(|(_, child_of)| child_of.parent() == character)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    const BODY: &str = "base/appearance/body.appearance.ron";
+    const OUTFIT: &str = "base/appearance/outfit.appearance.ron";
+
+    /// Keeps test manifests loaded.
+    #[derive(Resource)]
+    struct TestManifests(#[expect(dead_code)] Vec<Handle<AppearanceManifest>>);
+}
@@ -0,0 +1,40 @@
+use std::fmt::{self, Display, Formatter};
+
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{state::GameState, world::WorldName};
+
+pub(super) fn plugin(app: &mut App) {
+    app.replicate_resource::<Funds>().add_observer(init);
+}
+
+/// Inserts the default funds for new worlds and saves from older versions.
+fn init(_on: On<Add, WorldName>, mut commands: Commands, client_state: Res<State<ClientState>>) {
+    if *client_state == ClientState::Connected {
+        // Replicated from the server.
+        return;
+    }
+
+    commands.init_resource::<Funds>();
+}
+
+/// Money available for buying objects.
+#[derive(Resource, Reflect, Serialize, Deserialize, Deref, PartialEq, Clone, Copy)]
+#[component(immutable)]
+#[reflect(Resource)]
+#[require(DespawnOnExit::<_>(GameState::World))]
+pub struct Funds(pub u32);
+
+impl Default for Funds {
+    fn default() -> Self {
+        Self(20_000)
+    }
+}
+
+impl Display for Funds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "§{}", self.0)
+    }
+}
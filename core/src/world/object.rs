@@ -1,116 +1,392 @@
 pub mod placing;
+mod variant;
+
+use std::{any::Any, collections::VecDeque};
 
 use avian3d::prelude::*;
 use bevy::{
     asset::AssetPath,
     ecs::{entity::MapEntities, reflect::ReflectCommandExt},
+    platform::collections::HashMap,
     prelude::*,
 };
 use bevy_mod_outline::{AsyncWorldInheritOutline, OutlineVolume};
-use bevy_replicon::{prelude::*, shared::backend::connected_client::NetworkId};
-use bevy_replicon_renet::netcode::NetcodeClientTransport;
+use bevy_replicon::{
+    prelude::*,
+    shared::backend::connected_client::{ConnectedClient, NetworkId},
+};
 use serde::{Deserialize, Serialize};
 
+use variant::VariantMaterial;
+
 use crate::{
-    asset_manifest::object::ObjectManifest,
+    asset_manifest::{ManifestPaths, object::ObjectManifest},
+    network::{LocalNetworkId, PlayerId},
     state::GameState,
     undo::{
-        CommandId, ConfirmableCommand, EntityRecorder,
+        CommandExpired, CommandId, ConfirmableCommand, EntityRecorder,
         client_command::{
             ClientCommand, ClientCommandAppExt, ClientCommandExt, CommandRequest, Deny,
         },
+        revision::{PlayerHistories, Revision},
     },
     world::{
-        combined_collider::CombinedCollider, cursor::outline::OUTLINE_VOLUME, layer::GameLayer,
+        combined_collider::CombinedCollider, cursor::outline::OUTLINE_VOLUME, funds::Funds,
+        layer::GameLayer,
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((commands_plugin, placing::plugin, variant::plugin));
+}
+
+/// Replication and commands without player input, can be tested separately.
+fn commands_plugin(app: &mut App) {
     app.add_client_command::<MoveObject>()
+        .add_client_command::<ChangeVariant>()
         .add_client_command::<BuyObject>()
         .add_client_command::<SellObject>()
         .replicate::<Object>()
-        .add_plugins(placing::plugin)
+        .init_resource::<RecentSales>()
         .add_observer(init)
+        .add_systems(Update, reload)
         .add_observer(move_command)
+        .add_observer(change_variant)
         .add_observer(buy)
         .add_observer(buy_deny)
-        .add_observer(sell);
+        .add_observer(buy_expire)
+        .add_observer(sell)
+        .add_observer(forget_sales);
 }
 
 fn init(
     insert: On<Insert, Object>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
     manifests: Res<Assets<ObjectManifest>>,
-    mut objects: Query<(&Object, &mut Name, &mut WorldAssetRoot)>,
+    mut objects: Query<(
+        &Object,
+        &mut Name,
+        &mut WorldAssetRoot,
+        &mut VariantMaterial,
+        &mut ManifestComponents,
+    )>,
 ) {
-    let (object, mut name, mut asset_root) = objects.get_mut(insert.entity).unwrap();
+    let (object, mut name, mut asset_root, mut variant_material, mut applied) =
+        objects.get_mut(insert.entity).unwrap();
 
-    let Some(manifest_handle) = asset_server.get_handle(&object.manifest) else {
+    let Some(manifest_handle) = manifest_paths.get_handle(&asset_server, &object.manifest) else {
         error!("'{}' is missing, ignoring", object.manifest);
         return;
     };
 
+    let Some(manifest) = manifests.get(&manifest_handle) else {
+        // Will be applied by `reload` if the manifest is fixed on disk.
+        error!("'{}' is not loaded, ignoring", object.manifest);
+        return;
+    };
+
     debug!(
         "initializing object '{}' for `{}`",
         &object.manifest, insert.entity
     );
 
-    let manifest = manifests
-        .get(&manifest_handle)
-        .unwrap_or_else(|| panic!("'{:?}' should be loaded", object.manifest));
+    apply_manifest(
+        commands.entity(insert.entity),
+        &asset_server,
+        manifest,
+        &object.variant,
+        &mut name,
+        &mut asset_root,
+        &mut variant_material,
+        &mut applied,
+    );
+}
+
+/// Re-applies manifests modified on disk to objects that use them.
+///
+/// Manifests that failed to load emit [`AssetEvent::Added`] once fixed, so objects
+/// that couldn't be initialized are also picked up. Removed manifests keep the last
+/// applied state on objects until the manifest is loaded again.
+///
+/// Manifests are discovered only during [`GameState::ManifestsLoading`],
+/// so new manifest files require a restart.
+fn reload(
+    mut commands: Commands,
+    mut asset_events: MessageReader<AssetEvent<ObjectManifest>>,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    manifests: Res<Assets<ObjectManifest>>,
+    mut objects: Query<(
+        Entity,
+        &Object,
+        &mut Name,
+        &mut WorldAssetRoot,
+        &mut VariantMaterial,
+        &mut ManifestComponents,
+    )>,
+) {
+    for &event in asset_events.read() {
+        let id = match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => id,
+            AssetEvent::Removed { id } => {
+                warn!("manifest `{id:?}` was removed, keeping its objects unchanged");
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(manifest) = manifests.get(id) else {
+            continue;
+        };
+
+        for (entity, object, mut name, mut asset_root, mut variant_material, mut applied) in
+            &mut objects
+        {
+            if manifest_paths
+                .get_handle::<ObjectManifest>(&asset_server, &object.manifest)
+                .is_none_or(|handle| handle.id() != id)
+            {
+                continue;
+            }
 
+            debug!("reloading '{}' for `{entity}`", object.manifest);
+            apply_manifest(
+                commands.entity(entity),
+                &asset_server,
+                manifest,
+                &object.variant,
+                &mut name,
+                &mut asset_root,
+                &mut variant_material,
+                &mut applied,
+            );
+        }
+    }
+}
+
+/// Applies name, variant look and components from the manifest.
+///
+/// Components from the previously applied manifest are removed first.
+fn apply_manifest(
+    mut entity: EntityCommands,
+    asset_server: &AssetServer,
+    manifest: &ObjectManifest,
+    variant: &str,
+    name: &mut Name,
+    asset_root: &mut Mut<WorldAssetRoot>,
+    variant_material: &mut Mut<VariantMaterial>,
+    applied: &mut ManifestComponents,
+) {
     *name = manifest.info.name.clone();
-    **asset_root = asset_server.load(manifest.asset.clone());
+    variant::apply_variant(
+        asset_server,
+        manifest,
+        variant,
+        asset_root,
+        variant_material,
+    );
+
+    for &type_path in &**applied {
+        entity.remove_reflect(type_path);
+    }
+    applied.clear();
 
-    let mut entity = commands.entity(insert.entity);
     for component in &manifest.components {
-        entity.insert_reflect(component.as_ref().reflect_clone().unwrap());
+        let component = component.as_ref().reflect_clone().unwrap();
+        if let Some(info) = component.get_represented_type_info() {
+            applied.push(info.type_path());
+        }
+        entity.insert_reflect(component);
     }
 }
 
 fn move_command(
     move_command: On<ClientCommand<MoveObject>>,
     mut commands: Commands,
-    mut objects: Query<&mut Transform, With<Object>>,
+    mut histories: ResMut<PlayerHistories>,
+    clients: Query<&NetworkId>,
+    mut objects: Query<(&mut Transform, &mut Revision), With<Object>>,
 ) {
-    match objects.get_mut(move_command.object) {
-        Ok(mut transform) => {
+    let (mut transform, mut revision) = match objects.get_mut(move_command.object) {
+        Ok(object) => object,
+        Err(e) => {
             info!(
-                "`{:?}` moves `{}`",
+                "denying `{:?}` to move `{}`: {e}",
                 move_command.client_id, move_command.object
             );
-            transform.translation = move_command.translation;
-            transform.rotation = move_command.rotation;
-            commands.server_trigger(move_command.confirm());
+            commands.server_trigger(move_command.deny());
+            return;
         }
+    };
+
+    let player = PlayerId::new(move_command.client_id, &clients);
+    if move_command.check_conflicts
+        && let Err(conflict) = histories.check(move_command.object, &revision, player)
+    {
+        info!(
+            "denying `{:?}` to move `{}` changed by `{}`",
+            move_command.client_id, move_command.object, conflict.editor
+        );
+        commands.server_trigger(move_command.conflict(conflict));
+        return;
+    }
+
+    info!(
+        "`{:?}` moves `{}`",
+        move_command.client_id, move_command.object
+    );
+    transform.translation = move_command.translation;
+    transform.rotation = move_command.rotation;
+    histories.change(move_command.object, &mut revision, player);
+    commands.server_trigger(move_command.confirm());
+}
+
+fn change_variant(
+    change: On<ClientCommand<ChangeVariant>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    manifests: Res<Assets<ObjectManifest>>,
+    mut histories: ResMut<PlayerHistories>,
+    clients: Query<&NetworkId>,
+    mut objects: Query<(&Object, &mut Revision)>,
+) {
+    let (object, mut revision) = match objects.get_mut(change.object) {
+        Ok(object) => object,
         Err(e) => {
             info!(
-                "denying `{:?}` to move `{}`: {e}",
-                move_command.client_id, move_command.object
+                "denying `{:?}` to change variant of `{}`: {e}",
+                change.client_id, change.object
             );
-            commands.server_trigger(move_command.deny());
+            commands.server_trigger(change.deny());
+            return;
         }
+    };
+
+    let player = PlayerId::new(change.client_id, &clients);
+    if change.check_conflicts
+        && let Err(conflict) = histories.check(change.object, &revision, player)
+    {
+        info!(
+            "denying `{:?}` to change variant of `{}` changed by `{}`",
+            change.client_id, change.object, conflict.editor
+        );
+        commands.server_trigger(change.conflict(conflict));
+        return;
     }
+
+    if !find_manifest(&asset_server, &manifest_paths, &manifests, &object.manifest)
+        .is_some_and(|manifest| manifest.is_valid_variant(&change.variant))
+    {
+        info!(
+            "denying `{:?}` to change variant of `{}` to unknown '{}'",
+            change.client_id, change.object, change.variant
+        );
+        commands.server_trigger(change.deny());
+        return;
+    }
+
+    info!(
+        "`{:?}` changes variant of `{}` to '{}'",
+        change.client_id, change.object, change.variant
+    );
+    commands.entity(change.object).insert(Object {
+        manifest: object.manifest.clone(),
+        variant: change.variant.clone(),
+    });
+    histories.change(change.object, &mut revision, player);
+    commands.server_trigger(change.confirm());
 }
 
 fn buy(
     buy: On<ClientCommand<BuyObject>>,
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    manifests: Res<Assets<ObjectManifest>>,
+    funds: Res<Funds>,
+    mut histories: ResMut<PlayerHistories>,
+    mut sales: ResMut<RecentSales>,
     clients: Query<&NetworkId>,
     pending_objects: Query<(Entity, &PendingObject), Without<Object>>,
 ) {
+    let Some(manifest) = find_manifest(&asset_server, &manifest_paths, &manifests, &buy.manifest)
+    else {
+        info!(
+            "denying `{:?}` to buy missing '{:?}'",
+            buy.client_id, buy.manifest
+        );
+        commands.server_trigger(buy.deny());
+        return;
+    };
+
+    if !manifest.is_valid_variant(&buy.variant) {
+        info!(
+            "denying `{:?}` to buy '{:?}' with unknown variant '{}'",
+            buy.client_id, buy.manifest, buy.variant
+        );
+        commands.server_trigger(buy.deny());
+        return;
+    }
+
+    let player = PlayerId::new(buy.client_id, &clients);
+    let manifest_path = ManifestPaths::canonical(&buy.manifest);
+    let (price, sale_index) = match buy.pricing {
+        Pricing::Full => (manifest.price, None),
+        Pricing::Resale => {
+            let Some(index) = sales.position(player, &manifest_path) else {
+                info!(
+                    "denying `{:?}` to buy back '{:?}' that wasn't sold by `{player}`",
+                    buy.client_id, buy.manifest
+                );
+                commands.server_trigger(buy.deny());
+                return;
+            };
+            (sales.get(player, index).refund, Some(index))
+        }
+    };
+
+    let Some(remaining) = funds.checked_sub(price) else {
+        info!(
+            "denying `{:?}` to buy '{:?}' for {price} with {}",
+            buy.client_id, buy.manifest, *funds
+        );
+        commands.server_trigger(buy.deny());
+        return;
+    };
+
+    // Buying back keeps the price of the original purchase to refund it on undo.
+    let purchase_price = match sale_index {
+        Some(index) => {
+            let sale = sales.remove(player, index);
+            sale.purchase_price.unwrap_or(sale.refund)
+        }
+        None => price,
+    };
+
+    let revision = Revision::new(player);
     let bundle = (
         Object {
-            manifest: buy.manifest.clone(),
+            manifest: manifest_path,
+            variant: buy.variant.clone(),
         },
         Transform::from_translation(buy.translation).with_rotation(buy.rotation),
+        revision,
+        Purchase {
+            buyer: player,
+            price: purchase_price,
+        },
     );
 
-    if let ClientId::Client(client) = buy.client_id {
+    let object = if let ClientId::Client(client) = buy.client_id {
         let network_id = clients.get(client).unwrap();
-        commands.spawn((bundle, Signature::from((buy.id, network_id))));
+        commands
+            .spawn((bundle, Signature::from((buy.id, network_id))))
+            .id()
+    } else if buy.replayed {
+        // Placeholders are spawned by the command itself, so they don't exist in replays.
+        commands.spawn(bundle).id()
     } else {
         // While it's O(n), it's usually a single entity.
         let Some((object, _)) = pending_objects.iter().find(|(_, o)| o.id == buy.id) else {
@@ -120,10 +396,15 @@ fn buy(
         };
 
         commands.entity(object).insert(bundle);
+        object
     };
+    histories.spawn(object, revision);
 
-    info!("`{:?}` buys '{:?}'", buy.client_id, buy.manifest);
-
+    info!(
+        "`{:?}` buys '{:?}' for {price}",
+        buy.client_id, buy.manifest
+    );
+    commands.insert_resource(Funds(remaining));
     commands.server_trigger(buy.confirm());
 }
 
@@ -138,24 +419,103 @@ fn buy_deny(
     }
 }
 
+fn buy_expire(
+    expired: On<CommandExpired>,
+    mut commands: Commands,
+    pending_objects: Query<(Entity, &PendingObject), Without<Object>>,
+) {
+    if let Some((object, _)) = pending_objects.iter().find(|(_, o)| o.id == expired.id) {
+        debug!("despawning `{object}` from expired buy");
+        commands.entity(object).despawn();
+    }
+}
+
 fn sell(
     sell: On<ClientCommand<SellObject>>,
     mut commands: Commands,
-    objects: Query<(), With<Object>>,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    manifests: Res<Assets<ObjectManifest>>,
+    funds: Res<Funds>,
+    histories: Res<PlayerHistories>,
+    mut sales: ResMut<RecentSales>,
+    clients: Query<&NetworkId>,
+    objects: Query<(&Object, &Revision, Option<&Purchase>)>,
 ) {
-    match objects.get(sell.object) {
-        Ok(()) => {
-            info!("`{:?}` sells `{}`", sell.client_id, sell.object);
-            commands.entity(sell.object).despawn();
-            commands.server_trigger(sell.confirm());
-        }
+    let (object, revision, purchase) = match objects.get(sell.object) {
+        Ok(object) => object,
         Err(e) => {
             info!(
                 "denying `{:?}` to sell `{}`: {e}",
                 sell.client_id, sell.object
             );
             commands.server_trigger(sell.deny());
+            return;
+        }
+    };
+
+    let player = PlayerId::new(sell.client_id, &clients);
+    if sell.check_conflicts
+        && let Err(conflict) = histories.check(sell.object, revision, player)
+    {
+        info!(
+            "denying `{:?}` to sell `{}` changed by `{}`",
+            sell.client_id, sell.object, conflict.editor
+        );
+        commands.server_trigger(sell.conflict(conflict));
+        return;
+    }
+
+    let refund = match sell.pricing {
+        Pricing::Full => {
+            // Only the buyer can revert the purchase if nobody else changed the object since.
+            let Some(purchase) = purchase.filter(|purchase| {
+                purchase.buyer == player && histories.check(sell.object, revision, player).is_ok()
+            }) else {
+                info!(
+                    "denying `{:?}` to refund `{}` that wasn't bought by `{player}`",
+                    sell.client_id, sell.object
+                );
+                commands.server_trigger(sell.deny());
+                return;
+            };
+            purchase.price
+        }
+        Pricing::Resale => {
+            find_manifest(&asset_server, &manifest_paths, &manifests, &object.manifest)
+                .map(ObjectManifest::resale_price)
+                .unwrap_or_default()
         }
+    };
+
+    if let Pricing::Resale = sell.pricing {
+        sales.push(
+            player,
+            Sale {
+                manifest: object.manifest.clone(),
+                refund,
+                purchase_price: purchase.map(|purchase| purchase.price),
+            },
+        );
+    }
+
+    info!(
+        "`{:?}` sells `{}` for {refund}",
+        sell.client_id, sell.object
+    );
+    commands.insert_resource(Funds(funds.saturating_add(refund)));
+    commands.entity(sell.object).despawn();
+    commands.server_trigger(sell.confirm());
+}
+
+/// Drops sales of a disconnected client since it can't buy them back anymore.
+fn forget_sales(
+    remove: On<Remove, ConnectedClient>,
+    mut sales: ResMut<RecentSales>,
+    clients: Query<&NetworkId>,
+) {
+    if let Ok(network_id) = clients.get(remove.entity) {
+        sales.0.remove(&PlayerId::Client(network_id.get()));
     }
 }
 
@@ -165,6 +525,10 @@ struct MoveObject {
     object: Entity,
     translation: Vec3,
     rotation: Quat,
+
+    /// Whether the command comes from the history and should be denied
+    /// if the object was changed by another player since then.
+    check_conflicts: bool,
 }
 
 impl ConfirmableCommand for MoveObject {
@@ -176,14 +540,73 @@ impl ConfirmableCommand for MoveObject {
     ) -> Option<Box<dyn ConfirmableCommand>> {
         let transform = *world.get::<Transform>(self.object)?;
 
-        world.client_trigger(CommandRequest { id, command: *self });
+        world.client_trigger(CommandRequest::new(id, *self));
 
         Some(Box::new(Self {
             object: self.object,
             translation: transform.translation,
             rotation: transform.rotation,
+            check_conflicts: true,
         }))
     }
+
+    fn label(&self, world: &World) -> String {
+        format!("Move {}", object_name(world, self.object))
+    }
+
+    fn merge(&mut self, newer: &dyn Any) -> bool {
+        let Some(newer) = newer.downcast_ref::<Self>() else {
+            return false;
+        };
+        newer.object == self.object
+    }
+}
+
+#[derive(Serialize, Deserialize, MapEntities, Clone)]
+struct ChangeVariant {
+    #[entities]
+    object: Entity,
+
+    /// Like [`Object::variant`].
+    variant: String,
+
+    /// Like [`MoveObject::check_conflicts`].
+    check_conflicts: bool,
+}
+
+impl ConfirmableCommand for ChangeVariant {
+    fn apply(
+        self: Box<Self>,
+        id: CommandId,
+        _recorder: &mut EntityRecorder,
+        world: &mut World,
+    ) -> Option<Box<dyn ConfirmableCommand>> {
+        let object = self.object;
+        let variant = world.get::<Object>(object)?.variant.clone();
+
+        world.client_trigger(CommandRequest::new(id, *self));
+
+        Some(Box::new(Self {
+            object,
+            variant,
+            check_conflicts: true,
+        }))
+    }
+
+    fn label(&self, world: &World) -> String {
+        format!("Change variant of {}", object_name(world, self.object))
+    }
+
+    fn merge(&mut self, newer: &dyn Any) -> bool {
+        let Some(newer) = newer.downcast_ref::<Self>() else {
+            return false;
+        };
+        newer.object == self.object
+    }
+
+    fn heap_size(&self) -> usize {
+        self.variant.capacity()
+    }
 }
 
 #[derive(Serialize, Deserialize, MapEntities, Clone)]
@@ -191,6 +614,8 @@ struct BuyObject {
     manifest: AssetPath<'static>,
     translation: Vec3,
     rotation: Quat,
+    variant: String,
+    pricing: Pricing,
 }
 
 impl ConfirmableCommand for BuyObject {
@@ -203,24 +628,46 @@ impl ConfirmableCommand for BuyObject {
         // Spawns a placeholder entity immediately so undo has something to point at.
         // The entity will be matched by hash if spawned by client or manually matched by ID on the server.
         let pending_object = PendingObject { id };
-        let object = if let Some(transport) = world.get_resource::<NetcodeClientTransport>() {
+        let object = if let Some(&network_id) = world.get_resource::<LocalNetworkId>() {
             world
-                .spawn((
-                    pending_object,
-                    Signature::from((id, NetworkId::new(transport.client_id()))),
-                ))
+                .spawn((pending_object, Signature::from((id, *network_id))))
                 .id()
         } else {
             world.spawn(pending_object).id()
         };
         recorder.record(object);
 
-        world.client_trigger(CommandRequest { id, command: *self });
+        world.client_trigger(CommandRequest::new(id, *self));
+
+        Some(Box::new(SellObject {
+            object,
+            check_conflicts: true,
+            pricing: self.pricing,
+        }))
+    }
+
+    fn label(&self, world: &World) -> String {
+        let asset_server = world.resource::<AssetServer>();
+        let manifest_paths = world.resource::<ManifestPaths>();
+        let manifests = world.resource::<Assets<ObjectManifest>>();
+        let name = find_manifest(asset_server, manifest_paths, manifests, &self.manifest)
+            .map(|manifest| manifest.info.name.as_str())
+            .unwrap_or("object");
 
-        Some(Box::new(SellObject { object }))
+        format!("Buy {name}")
+    }
+
+    fn heap_size(&self) -> usize {
+        self.manifest.path().as_os_str().len() + self.variant.capacity()
     }
 }
 
+/// Type paths of components inserted from the object manifest.
+///
+/// Used to remove them when the manifest is reloaded.
+#[derive(Component, Default, Deref, DerefMut)]
+struct ManifestComponents(Vec<&'static str>);
+
 #[derive(Component)]
 struct PendingObject {
     id: CommandId,
@@ -230,6 +677,10 @@ struct PendingObject {
 struct SellObject {
     #[entities]
     object: Entity,
+
+    /// Like [`MoveObject::check_conflicts`].
+    check_conflicts: bool,
+    pricing: Pricing,
 }
 
 impl ConfirmableCommand for SellObject {
@@ -243,23 +694,122 @@ impl ConfirmableCommand for SellObject {
         let object = entity.get::<Object>()?;
         let transform = *entity.get::<Transform>()?;
         let manifest = object.manifest.clone();
+        let variant = object.variant.clone();
 
-        world.client_trigger(CommandRequest { id, command: *self });
+        world.client_trigger(CommandRequest::new(id, *self));
         recorder.record(self.object);
 
         Some(Box::new(BuyObject {
             manifest,
             translation: transform.translation,
             rotation: transform.rotation,
+            variant,
+            pricing: self.pricing,
         }))
     }
+
+    fn label(&self, world: &World) -> String {
+        format!("Sell {}", object_name(world, self.object))
+    }
+}
+
+/// Amount of funds requested for an object.
+///
+/// Commands from the history keep the pricing of the reverted command,
+/// so undoing a buy refunds the full price and undoing a sell buys the object back
+/// for the amount it was sold for.
+///
+/// The server decides the actual amount from [`Purchase`] and [`RecentSales`]
+/// and denies requests that don't revert a transaction of the same player.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+enum Pricing {
+    /// [`ObjectManifest::price`] for buying or the price of the purchase for selling.
+    Full,
+    /// [`ObjectManifest::resale_price`] for selling or the refund of the sale for buying.
+    Resale,
+}
+
+/// Price paid for an object, tracked on the server.
+///
+/// Buying back a sold object keeps the price of the original purchase.
+#[derive(Component, Clone, Copy)]
+struct Purchase {
+    buyer: PlayerId,
+    price: u32,
+}
+
+/// Recent sales by each player, tracked on the server.
+///
+/// Used to validate buying objects back when players undo their sales.
+#[derive(Resource, Default)]
+struct RecentSales(HashMap<PlayerId, VecDeque<Sale>>);
+
+impl RecentSales {
+    fn push(&mut self, player: PlayerId, sale: Sale) {
+        let sales = self.0.entry(player).or_default();
+        if sales.len() >= MAX_RECENT_SALES {
+            sales.pop_front();
+        }
+        sales.push_back(sale);
+    }
+
+    /// Returns the index of the latest sale of the manifest by the player.
+    fn position(&self, player: PlayerId, manifest: &AssetPath) -> Option<usize> {
+        self.0
+            .get(&player)?
+            .iter()
+            .rposition(|sale| sale.manifest == *manifest)
+    }
+
+    fn get(&self, player: PlayerId, index: usize) -> &Sale {
+        &self.0[&player][index]
+    }
+
+    fn remove(&mut self, player: PlayerId, index: usize) -> Sale {
+        self.0
+            .get_mut(&player)
+            .and_then(|sales| sales.remove(index))
+            .expect("sale should be found with `position`")
+    }
+}
+
+/// Maximum number of sales remembered per player.
+const MAX_RECENT_SALES: usize = 64;
+
+struct Sale {
+    manifest: AssetPath<'static>,
+    refund: u32,
+
+    /// Price of the purchase if the object was bought.
+    purchase_price: Option<u32>,
+}
+
+fn find_manifest<'a>(
+    asset_server: &AssetServer,
+    manifest_paths: &ManifestPaths,
+    manifests: &'a Assets<ObjectManifest>,
+    path: &AssetPath,
+) -> Option<&'a ObjectManifest> {
+    let handle = manifest_paths.get_handle(asset_server, path)?;
+    manifests.get(&handle)
+}
+
+fn object_name(world: &World, object: Entity) -> &str {
+    world
+        .get::<Name>(object)
+        .map(Name::as_str)
+        .filter(|name| !name.is_empty())
+        .unwrap_or("object")
 }
 
-#[derive(Component, Reflect, Serialize, Deserialize)]
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, PartialEq)]
 #[require(
     Name,
     Replicated,
+    Revision,
+    ManifestComponents,
     WorldAssetRoot,
+    VariantMaterial,
     AsyncWorldInheritOutline,
     RigidBody::Kinematic,
     OutlineVolume = OUTLINE_VOLUME,
@@ -270,5 +820,600 @@ impl ConfirmableCommand for SellObject {
 #[component(immutable)]
 #[reflect(Component)]
 pub struct Object {
+    /// Path to the manifest without a source, see [`ManifestPaths`].
     pub manifest: AssetPath<'static>,
+
+    /// Name from [`ObjectManifest::variants`], empty for the default look.
+    #[reflect(default)]
+    #[serde(default)]
+    pub variant: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::system::SystemState, time::TimeUpdateStrategy};
+    use test_log::test;
+
+    use super::*;
+    use crate::{
+        asset_manifest::object::ObjectVariant,
+        replay,
+        test_session::TestSession,
+        undo::{HistoryCommands, PENDING_TIMEOUT, history::CommandHistory, revision::Conflict},
+    };
+
+    #[test]
+    fn move_undo() {
+        let mut session = TestSession::new(1, setup);
+        let server_object = spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        queue_move(session.client(0), object, Vec3::ONE);
+        session.update();
+        session.update();
+
+        let transform = session
+            .server
+            .world()
+            .get::<Transform>(server_object)
+            .unwrap();
+        assert_eq!(transform.translation, Vec3::ONE);
+
+        let history = session.client(0).world().resource::<CommandHistory>();
+        assert!(history.undo_labels().eq(["Move Chair"]));
+
+        undo(session.client(0));
+        session.update();
+        session.update();
+
+        let transform = session
+            .server
+            .world()
+            .get::<Transform>(server_object)
+            .unwrap();
+        assert_eq!(transform.translation, Vec3::ZERO);
+        session.assert_replicated::<Transform>(server_object);
+    }
+
+    #[test]
+    fn change_variant() {
+        let mut session = TestSession::new(1, setup);
+        let server_object = spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        let mut state = SystemState::<HistoryCommands>::new(session.client(0).world_mut());
+        let mut commands = state.get_mut(session.client(0).world_mut()).unwrap();
+        commands.queue_confirmable(ChangeVariant {
+            object,
+            variant: "Red".to_string(),
+            check_conflicts: false,
+        });
+        state.apply(session.client(0).world_mut());
+        session.update();
+        session.update();
+
+        let server_variant = session
+            .server
+            .world()
+            .get::<Object>(server_object)
+            .unwrap()
+            .variant
+            .clone();
+        assert_eq!(server_variant, "Red");
+        session.assert_replicated::<Object>(server_object);
+
+        let history = session.client(0).world().resource::<CommandHistory>();
+        assert!(history.undo_labels().eq(["Change variant of Chair"]));
+
+        undo(session.client(0));
+        session.update();
+        session.update();
+
+        let server_variant = session
+            .server
+            .world()
+            .get::<Object>(server_object)
+            .unwrap()
+            .variant
+            .clone();
+        assert!(server_variant.is_empty());
+        session.assert_replicated::<Object>(server_object);
+    }
+
+    #[test]
+    fn change_variant_unknown() {
+        let mut session = TestSession::new(1, setup);
+        let server_object = spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        let mut state = SystemState::<HistoryCommands>::new(session.client(0).world_mut());
+        let mut commands = state.get_mut(session.client(0).world_mut()).unwrap();
+        commands.queue_confirmable(ChangeVariant {
+            object,
+            variant: "Green".to_string(),
+            check_conflicts: false,
+        });
+        state.apply(session.client(0).world_mut());
+        session.update();
+        session.update();
+
+        let server_variant = &session
+            .server
+            .world()
+            .get::<Object>(server_object)
+            .unwrap()
+            .variant;
+        assert!(
+            server_variant.is_empty(),
+            "unknown variants should be denied"
+        );
+
+        let history = session.client(0).world().resource::<CommandHistory>();
+        assert_eq!(history.undo_labels().len(), 0);
+    }
+
+    #[test]
+    fn reload_modified() {
+        let mut session = TestSession::new(1, setup);
+        let server_object = spawn_object(&mut session);
+
+        let handle = session.server.world().resource::<TestManifest>().0.clone();
+        let mut manifests = session
+            .server
+            .world_mut()
+            .resource_mut::<Assets<ObjectManifest>>();
+        manifests.get_mut(&handle).unwrap().info.name = "Table".into();
+        session.update();
+        session.update();
+
+        let name = session.server.world().get::<Name>(server_object).unwrap();
+        assert_eq!(name.as_str(), "Table");
+    }
+
+    #[test]
+    fn reload_added() {
+        let mut session = TestSession::new(1, setup);
+        let handle = session.server.world().resource::<TestManifest>().0.clone();
+        let manifest = session
+            .server
+            .world_mut()
+            .resource_mut::<Assets<ObjectManifest>>()
+            .remove(&handle)
+            .unwrap();
+        let server_object = spawn_object(&mut session);
+        session.update();
+
+        let name = session.server.world().get::<Name>(server_object).unwrap();
+        assert_ne!(
+            name.as_str(),
+            "Chair",
+            "object shouldn't be initialized without a manifest"
+        );
+
+        session
+            .server
+            .world_mut()
+            .resource_mut::<Assets<ObjectManifest>>()
+            .insert(&handle, manifest)
+            .unwrap();
+        session.update();
+        session.update();
+
+        let name = session.server.world().get::<Name>(server_object).unwrap();
+        assert_eq!(name.as_str(), "Chair");
+    }
+
+    #[test]
+    fn sell() {
+        let mut session = TestSession::new(1, setup);
+        spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        queue_sell(session.client(0), object, Pricing::Resale);
+        session.update();
+        session.update();
+
+        let mut server_objects = session.server.world_mut().query::<&Object>();
+        assert_eq!(server_objects.iter(session.server.world()).count(), 0);
+
+        let mut client_objects = session.client(0).world_mut().query::<&Object>();
+        assert_eq!(
+            client_objects.iter(session.client(0).world()).count(),
+            0,
+            "despawn should be replicated"
+        );
+
+        let history = session.client(0).world().resource::<CommandHistory>();
+        assert_eq!(history.undo_labels().len(), 1);
+    }
+
+    #[test]
+    fn sell_undo() {
+        let mut session = TestSession::new(1, setup);
+        spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        queue_sell(session.client(0), object, Pricing::Resale);
+        session.update();
+        session.update();
+        assert_eq!(**session.server.world().resource::<Funds>(), 20_050);
+
+        undo(session.client(0));
+        session.update();
+        session.update();
+        assert_eq!(
+            **session.server.world().resource::<Funds>(),
+            20_000,
+            "undo should buy the object back for the refunded amount"
+        );
+
+        let mut objects = session.server.world_mut().query::<&Object>();
+        assert_eq!(objects.iter(session.server.world()).count(), 1);
+    }
+
+    #[test]
+    fn sell_full_not_bought() {
+        let mut session = TestSession::new(1, setup);
+        spawn_object(&mut session);
+
+        let object = single_object(session.client(0));
+        queue_sell(session.client(0), object, Pricing::Full);
+        session.update();
+        session.update();
+
+        let mut objects = session.server.world_mut().query::<&Object>();
+        assert_eq!(
+            objects.iter(session.server.world()).count(),
+            1,
+            "full refund should be denied for objects not bought by the player"
+        );
+        assert_eq!(**session.server.world().resource::<Funds>(), 20_000);
+    }
+
+    #[test]
+    fn buy_resale_not_sold() {
+        let mut session = TestSession::new(1, setup);
+
+        let mut state = SystemState::<HistoryCommands>::new(session.server.world_mut());
+        let mut commands = state.get_mut(session.server.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: MANIFEST.into(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            variant: Default::default(),
+            pricing: Pricing::Resale,
+        });
+        state.apply(session.server.world_mut());
+        session.update();
+
+        let mut objects = session.server.world_mut().query::<&Object>();
+        assert_eq!(
+            objects.iter(session.server.world()).count(),
+            0,
+            "buying for the resale price should be denied without a sale"
+        );
+        assert_eq!(**session.server.world().resource::<Funds>(), 20_000);
+    }
+
+    #[test]
+    fn conflict() {
+        let mut session = TestSession::new(2, setup);
+        let server_object = spawn_object(&mut session);
+        session
+            .client(0)
+            .init_resource::<LastConflict>()
+            .add_observer(|conflict: On<Conflict>, mut last: ResMut<LastConflict>| {
+                **last = Some(*conflict);
+            });
+
+        let first_object = single_object(session.client(0));
+        queue_move(session.client(0), first_object, Vec3::ONE);
+        session.update();
+        session.update();
+
+        let second_object = single_object(session.client(1));
+        queue_move(session.client(1), second_object, Vec3::splat(2.0));
+        session.update();
+        session.update();
+
+        undo(session.client(0));
+        session.update();
+        session.update();
+
+        let transform = session
+            .server
+            .world()
+            .get::<Transform>(server_object)
+            .unwrap();
+        assert_eq!(
+            transform.translation,
+            Vec3::splat(2.0),
+            "undo should be denied"
+        );
+
+        let conflict = session
+            .client(0)
+            .world()
+            .resource::<LastConflict>()
+            .expect("conflict should be reported");
+        let network_id = session.network_id(1);
+        assert_eq!(conflict.editor, PlayerId::Client(network_id.get()));
+    }
+
+    #[test]
+    fn buy_client() {
+        let mut session = TestSession::new(1, setup);
+
+        let client = session.client(0);
+        let mut state = SystemState::<HistoryCommands>::new(client.world_mut());
+        let mut commands = state.get_mut(client.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: MANIFEST.into(),
+            translation: Vec3::ONE,
+            rotation: Quat::IDENTITY,
+            variant: "Red".to_string(),
+            pricing: Pricing::Full,
+        });
+        state.apply(client.world_mut());
+
+        let mut placeholders = client
+            .world_mut()
+            .query_filtered::<Entity, (With<PendingObject>, With<Signature>)>();
+        let placeholder = placeholders
+            .single(client.world())
+            .expect("placeholder should be spawned with a signature");
+
+        session.update();
+        session.update();
+
+        let server_object = single_object(&mut session.server);
+        assert_eq!(
+            session.client_entity(0, server_object),
+            placeholder,
+            "placeholder should be matched by signature"
+        );
+        session.assert_replicated::<Object>(server_object);
+        session.assert_replicated::<Transform>(server_object);
+        assert_eq!(**session.server.world().resource::<Funds>(), 19_900);
+
+        let history = session.client(0).world().resource::<CommandHistory>();
+        assert_eq!(history.undo_labels().len(), 1, "buy should be confirmed");
+    }
+
+    #[test]
+    fn buy_expire() {
+        let mut session = TestSession::new(1, setup);
+
+        let client = session.client(0);
+        client.insert_resource(TimeUpdateStrategy::ManualDuration(
+            PENDING_TIMEOUT + Duration::from_secs(1),
+        ));
+        let mut state = SystemState::<HistoryCommands>::new(client.world_mut());
+        let mut commands = state.get_mut(client.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: MANIFEST.into(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            variant: Default::default(),
+            pricing: Pricing::Full,
+        });
+        state.apply(client.world_mut());
+
+        // Update only the client to never deliver the request.
+        client.update();
+        client.update();
+
+        let mut placeholders = client.world_mut().query::<&PendingObject>();
+        assert_eq!(
+            placeholders.iter(client.world()).count(),
+            0,
+            "placeholder should be despawned after the timeout"
+        );
+    }
+
+    #[test]
+    fn buy_move_undo() {
+        let mut session = TestSession::new(1, setup);
+        session.server.init_resource::<LastConflict>().add_observer(
+            |conflict: On<Conflict>, mut last: ResMut<LastConflict>| {
+                **last = Some(*conflict);
+            },
+        );
+
+        let mut state = SystemState::<HistoryCommands>::new(session.server.world_mut());
+        let mut commands = state.get_mut(session.server.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: MANIFEST.into(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            variant: Default::default(),
+            pricing: Pricing::Full,
+        });
+        state.apply(session.server.world_mut());
+        session.update();
+
+        let object = single_object(&mut session.server);
+        queue_move(&mut session.server, object, Vec3::ONE);
+        session.update();
+
+        undo(&mut session.server);
+        session.update();
+
+        let transform = session.server.world().get::<Transform>(object).unwrap();
+        assert_eq!(transform.translation, Vec3::ZERO);
+
+        undo(&mut session.server);
+        session.update();
+
+        let mut objects = session.server.world_mut().query::<&Object>();
+        assert_eq!(
+            objects.iter(session.server.world()).count(),
+            0,
+            "own changes shouldn't conflict"
+        );
+        assert!(session.server.world().resource::<LastConflict>().is_none());
+    }
+
+    #[test]
+    fn buy_undo_redo() {
+        let mut session = TestSession::new(1, setup);
+
+        let mut state = SystemState::<HistoryCommands>::new(session.server.world_mut());
+        let mut commands = state.get_mut(session.server.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: MANIFEST.into(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            variant: Default::default(),
+            pricing: Pricing::Full,
+        });
+        state.apply(session.server.world_mut());
+        session.update();
+        assert_eq!(**session.server.world().resource::<Funds>(), 19_900);
+
+        undo(&mut session.server);
+        session.update();
+        assert_eq!(
+            **session.server.world().resource::<Funds>(),
+            20_000,
+            "undo should refund the full price"
+        );
+
+        let mut commands = state.get_mut(session.server.world_mut()).unwrap();
+        commands.redo();
+        state.apply(session.server.world_mut());
+        session.update();
+        assert_eq!(**session.server.world().resource::<Funds>(), 19_900);
+
+        undo(&mut session.server);
+        session.update();
+        assert_eq!(**session.server.world().resource::<Funds>(), 20_000);
+    }
+
+    #[test]
+    fn buy_missing() {
+        let mut session = TestSession::new(1, setup);
+
+        let mut state = SystemState::<HistoryCommands>::new(session.server.world_mut());
+        let mut commands = state.get_mut(session.server.world_mut()).unwrap();
+        commands.queue_confirmable(BuyObject {
+            manifest: "base/objects/missing.object.ron".into(),
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            variant: Default::default(),
+            pricing: Pricing::Full,
+        });
+        state.apply(session.server.world_mut());
+        session.update();
+
+        let mut objects = session.server.world_mut().query::<&PendingObject>();
+        assert_eq!(
+            objects.iter(session.server.world()).count(),
+            0,
+            "placeholder should be despawned on deny"
+        );
+        assert_eq!(**session.server.world().resource::<Funds>(), 20_000);
+    }
+
+    #[test]
+    fn empty_name() {
+        let mut world = World::new();
+        let object = world.spawn(Name::default()).id();
+        assert_eq!(object_name(&world, object), "object");
+    }
+
+    fn setup(app: &mut App) {
+        app.init_asset::<ObjectManifest>()
+            .init_resource::<ManifestPaths>()
+            .init_resource::<Funds>()
+            .replicate::<Transform>()
+            .add_plugins((replay::plugin, crate::undo::plugin, commands_plugin));
+
+        let handle = app
+            .world()
+            .resource::<AssetServer>()
+            .get_or_create_path_handle::<ObjectManifest>(MANIFEST, None);
+        let mut manifest = ObjectManifest {
+            variants: ["Blue", "Red"]
+                .map(|name| ObjectVariant {
+                    name: name.to_string(),
+                    asset: None,
+                    material: None,
+                })
+                .into(),
+            price: 100,
+            depreciation: 0.5,
+            ..Default::default()
+        };
+        manifest.info.name = "Chair".into();
+        app.world_mut()
+            .resource_mut::<Assets<ObjectManifest>>()
+            .insert(&handle, manifest)
+            .unwrap();
+        app.insert_resource(TestManifest(handle));
+    }
+
+    /// Spawns an object on the server and replicates it to clients.
+    fn spawn_object(session: &mut TestSession) -> Entity {
+        let object = session
+            .server
+            .world_mut()
+            .spawn((
+                Object {
+                    manifest: MANIFEST.into(),
+                    variant: Default::default(),
+                },
+                Transform::default(),
+            ))
+            .id();
+        session.update();
+
+        object
+    }
+
+    fn single_object(app: &mut App) -> Entity {
+        let mut objects = app.world_mut().query_filtered::<Entity, With<Object>>();
+        objects.single(app.world()).unwrap()
+    }
+
+    fn queue_move(client: &mut App, object: Entity, translation: Vec3) {
+        let mut state = SystemState::<HistoryCommands>::new(client.world_mut());
+        let mut commands = state.get_mut(client.world_mut()).unwrap();
+        commands.queue_confirmable(MoveObject {
+            object,
+            translation,
+            rotation: Quat::IDENTITY,
+            check_conflicts: false,
+        });
+        state.apply(client.world_mut());
+    }
+
+    fn queue_sell(client: &mut App, object: Entity, pricing: Pricing) {
+        let mut state = SystemState::<HistoryCommands>::new(client.world_mut());
+        let mut commands = state.get_mut(client.world_mut()).unwrap();
+        commands.queue_confirmable(SellObject {
+            object,
+            check_conflicts: false,
+            pricing,
+        });
+        state.apply(client.world_mut());
+    }
+
+    fn undo(app: &mut App) {
+        let mut state = SystemState::<HistoryCommands>::new(app.world_mut());
+        let mut commands = state.get_mut(app.world_mut()).unwrap();
+        commands.undo();
+        state.apply(app.world_mut());
+    }
+
+    const MANIFEST: &str = "base/objects/test.object.ron";
+
+    /// Keeps the test manifest loaded.
+    #[derive(Resource)]
+    struct TestManifest(Handle<ObjectManifest>);
+
+    #[derive(Resource, Default, Deref, DerefMut)]
+    struct LastConflict(Option<Conflict>);
 }
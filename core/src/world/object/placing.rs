@@ -7,18 +7,23 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy_enhanced_input::prelude::*;
 
-use crate::world::{
-    combined_collider::CombinedCollider,
-    cursor::{caster::CursorMask, follower::CursorFollower},
-    layer::GameLayer,
-    placing::intersection::BlockOnIntersection,
-    player_camera::HOLD_TO_PAN,
+use super::variant::{self, VariantMaterial};
+use crate::{
+    asset_manifest::object::ObjectManifest,
+    world::{
+        combined_collider::CombinedCollider,
+        cursor::{caster::CursorMask, follower::CursorFollower},
+        layer::GameLayer,
+        placing::intersection::BlockOnIntersection,
+        player_camera::HOLD_TO_PAN,
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((spawning::plugin, moving::plugin))
         .add_input_context::<PlacingObject>()
         .add_observer(rotate)
+        .add_observer(switch_variant)
         .add_observer(cancel);
 }
 
@@ -29,15 +34,58 @@ fn rotate(_on: On<Fire<Rotate>>, mut transform: Single<&mut Transform, With<Plac
     info!("rotating to '{}'", yaw.to_degrees());
 }
 
+fn switch_variant(
+    switch: On<Start<SwitchVariant>>,
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<ObjectManifest>>,
+    mut placing_objects: Query<(
+        &PlacingManifest,
+        &mut SelectedVariant,
+        &mut WorldAssetRoot,
+        &mut VariantMaterial,
+    )>,
+) {
+    let Ok((&manifest_id, mut selected, mut asset_root, mut variant_material)) =
+        placing_objects.get_mut(switch.context)
+    else {
+        return;
+    };
+    let Some(manifest) = manifests.get(*manifest_id) else {
+        return;
+    };
+    let Some(next) = manifest.next_variant(&selected) else {
+        return;
+    };
+
+    info!("switching `{}` to variant '{next}'", switch.context);
+    **selected = next.to_string();
+
+    variant::apply_variant(
+        &asset_server,
+        manifest,
+        &selected,
+        &mut asset_root,
+        &mut variant_material,
+    );
+}
+
 fn cancel(cancel: On<Start<Cancel>>, mut commands: Commands) {
     info!("cancelling");
     commands.entity(cancel.context).despawn();
 }
 
 #[derive(Component, Default)]
-#[require(CursorFollower)]
+#[require(CursorFollower, SelectedVariant)]
 struct PlacingObject;
 
+/// Manifest of the object that is being placed.
+#[derive(Component, Deref, Clone, Copy)]
+struct PlacingManifest(AssetId<ObjectManifest>);
+
+/// Name from [`ObjectManifest::variants`] that will be used for the placed object.
+#[derive(Component, Default, Deref, DerefMut, Clone)]
+struct SelectedVariant(String);
+
 pub fn placing_object() -> impl Bundle {
     (
         PlacingObject,
@@ -58,6 +106,15 @@ pub fn placing_object() -> impl Bundle {
                         Spawn(Binding::from(GamepadButton::West)),
                     )),
                 ),
+                (
+                    Action::<SwitchVariant>::new(),
+                    ActionSettings {
+                        consume_input: true,
+                        require_reset: true,
+                        ..Default::default()
+                    },
+                    bindings![KeyCode::KeyV, GamepadButton::RightTrigger]
+                ),
                 (
                     Action::<Place>::new(),
                     ActionSettings {
@@ -85,6 +142,10 @@ pub fn placing_object() -> impl Bundle {
 #[action_output(f32)]
 struct Rotate;
 
+#[derive(InputAction)]
+#[action_output(bool)]
+struct SwitchVariant;
+
 #[derive(InputAction)]
 #[action_output(bool)]
 struct Place;
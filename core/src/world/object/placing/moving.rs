@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use bevy_enhanced_input::prelude::*;
 
-use super::{Place, PlacingObject};
+use super::{Place, PlacingManifest, PlacingObject, SelectedVariant};
 use crate::{
+    asset_manifest::{ManifestPaths, object::ObjectManifest},
     state::BuildingMode,
     undo::{HistoryCommands, client_command::DespawnOnResponse},
     world::{
@@ -11,7 +12,10 @@ use crate::{
             follower::CursorOffset,
         },
         layer::GameLayer,
-        object::{MoveObject, Object, SellObject, placing::placing_object},
+        object::{
+            ChangeVariant, MoveObject, Object, Pricing, SellObject, placing::placing_object,
+            variant::VariantMaterial,
+        },
         placing::PlacingBlockers,
         preview::PreviewOf,
     },
@@ -49,23 +53,27 @@ fn pick(
     _on: On<Start<Pick>>,
     cursor_target: Single<&CursorTarget>,
     mut commands: Commands,
-    objects: Query<(&WorldAssetRoot, &Transform), With<Object>>,
+    asset_server: Res<AssetServer>,
+    manifest_paths: Res<ManifestPaths>,
+    objects: Query<(&Object, &WorldAssetRoot, &VariantMaterial, &Transform)>,
 ) {
     let Some(target) = ***cursor_target else {
         return;
     };
-    let Ok((asset_root, transform)) = objects.get(target) else {
+    let Ok((object, asset_root, variant_material, transform)) = objects.get(target) else {
         return;
     };
 
     info!("picking `{target}`");
-    commands.spawn((
+    let mut moving = commands.spawn((
         Name::new("Moving object"),
         placing_object(),
         MovingObject,
         ContextPriority::<MovingObject>::new(100),
         PreviewOf { target },
+        SelectedVariant(object.variant.clone()),
         asset_root.clone(),
+        variant_material.clone(),
         *transform,
         CursorOffset::default(),
         DespawnOnExit(BuildingMode::Objects),
@@ -81,24 +89,54 @@ fn pick(
             ),
         ]),
     ));
+
+    // Without a manifest the variant can't be switched, but the object can still be moved.
+    if let Some(handle) =
+        manifest_paths.get_handle::<ObjectManifest>(&asset_server, &object.manifest)
+    {
+        moving.insert(PlacingManifest(handle.id()));
+    }
 }
 
 fn place(
     place: On<Start<Place>>,
     mut commands: HistoryCommands,
-    moving_object: Single<(&PreviewOf, &Transform, &PlacingBlockers), With<MovingObject>>,
+    objects: Query<&Object>,
+    moving_object: Single<
+        (&PreviewOf, &SelectedVariant, &Transform, &PlacingBlockers),
+        With<MovingObject>,
+    >,
 ) {
-    let (preview, transform, blockers) = *moving_object;
+    let (preview, selected, transform, blockers) = *moving_object;
     if !blockers.is_empty() {
         return;
     }
 
+    let object = objects.get(preview.target).unwrap();
+    let variant_changed = object.variant != **selected;
+
     info!("moving `{}`", preview.target);
-    let id = commands.queue_confirmable(MoveObject {
+    if variant_changed {
+        commands.begin_group();
+    }
+    let mut id = commands.queue_confirmable(MoveObject {
         object: preview.target,
         translation: transform.translation,
         rotation: transform.rotation,
+        check_conflicts: false,
     });
+    if variant_changed {
+        info!(
+            "changing variant of `{}` to '{}'",
+            preview.target, **selected
+        );
+        id = commands.queue_confirmable(ChangeVariant {
+            object: preview.target,
+            variant: (**selected).clone(),
+            check_conflicts: false,
+        });
+        commands.end_group();
+    }
 
     commands
         .entity(place.context)
@@ -117,6 +155,8 @@ fn sell(
 
     let id = commands.queue_confirmable(SellObject {
         object: preview.target,
+        check_conflicts: false,
+        pricing: Pricing::Resale,
     });
 
     commands
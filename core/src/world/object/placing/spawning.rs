@@ -1,12 +1,16 @@
 use bevy::prelude::*;
 use bevy_enhanced_input::prelude::*;
 
-use super::{Place, PlacingObject};
+use super::{Place, PlacingManifest, PlacingObject, SelectedVariant};
 use crate::{
-    asset_manifest::object::ObjectManifest,
+    asset_manifest::{ManifestPaths, object::ObjectManifest},
     undo::{HistoryCommands, client_command::DespawnOnResponse},
     world::{
-        object::{BuyObject, placing::placing_object},
+        object::{
+            BuyObject, Pricing,
+            placing::placing_object,
+            variant::{self, VariantMaterial},
+        },
         placing::PlacingBlockers,
     },
 };
@@ -19,37 +23,60 @@ fn init(
     insert: On<Insert, SpawningObject>,
     asset_server: Res<AssetServer>,
     manifests: Res<Assets<ObjectManifest>>,
-    mut spawning_objects: Query<(&SpawningObject, &mut WorldAssetRoot)>,
+    mut spawning_objects: Query<
+        (
+            &PlacingManifest,
+            &SelectedVariant,
+            &mut WorldAssetRoot,
+            &mut VariantMaterial,
+        ),
+        With<SpawningObject>,
+    >,
 ) {
-    let (spawning, mut asset_root) = spawning_objects.get_mut(insert.entity).unwrap();
+    let (&manifest_id, selected, mut asset_root, mut variant_material) =
+        spawning_objects.get_mut(insert.entity).unwrap();
     let manifest = manifests
-        .get(spawning.id)
+        .get(*manifest_id)
         .expect("manifests should be preloaded");
 
-    debug!("loading asset `{}`", manifest.asset);
-
-    **asset_root = asset_server.load(manifest.asset.clone());
+    variant::apply_variant(
+        &asset_server,
+        manifest,
+        selected,
+        &mut asset_root,
+        &mut variant_material,
+    );
 }
 
 fn buy(
     buy: On<Start<Place>>,
     mut commands: HistoryCommands,
     asset_server: Res<AssetServer>,
-    spawning_object: Single<(&SpawningObject, &Transform, &PlacingBlockers)>,
+    spawning_object: Single<
+        (
+            &PlacingManifest,
+            &SelectedVariant,
+            &Transform,
+            &PlacingBlockers,
+        ),
+        With<SpawningObject>,
+    >,
 ) {
-    let (spawning, transform, blockers) = *spawning_object;
+    let (&manifest_id, selected, transform, blockers) = *spawning_object;
     if !blockers.is_empty() {
         return;
     }
 
-    let manifest = asset_server.get_path(spawning.id).unwrap();
+    let manifest = asset_server.get_path(*manifest_id).unwrap();
 
     info!("spawning '{manifest}'");
 
     let id = commands.queue_confirmable(BuyObject {
-        manifest: manifest.clone_owned(),
+        manifest: ManifestPaths::canonical(&manifest),
         translation: transform.translation,
         rotation: transform.rotation,
+        variant: (**selected).clone(),
+        pricing: Pricing::Full,
     });
 
     commands
@@ -63,14 +90,14 @@ pub fn spawning_object(id: AssetId<ObjectManifest>) -> impl Bundle {
     (
         Name::new("Spawning object"),
         placing_object(),
-        SpawningObject { id },
+        SpawningObject,
+        PlacingManifest(id),
         WorldAssetRoot::default(),
+        VariantMaterial::default(),
     )
 }
 
 #[derive(Component)]
 #[component(immutable)]
 #[require(PlacingObject)]
-pub(super) struct SpawningObject {
-    id: AssetId<ObjectManifest>,
-}
+pub(super) struct SpawningObject;
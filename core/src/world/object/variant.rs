@@ -0,0 +1,64 @@
+use std::iter;
+
+use bevy::{prelude::*, world_serialization::WorldInstanceReady};
+
+use crate::{asset_manifest::object::ObjectManifest, world::placing::tint::Tint};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(init_asset_instance);
+}
+
+fn init_asset_instance(
+    ready: On<WorldInstanceReady>,
+    mut commands: Commands,
+    variant_materials: Query<(&VariantMaterial, Option<&Tint>)>,
+    children: Query<&Children>,
+    mut material_handles: Query<&mut MeshMaterial3d<StandardMaterial>>,
+) {
+    let Ok((variant_material, tint)) = variant_materials.get(ready.entity) else {
+        return;
+    };
+    let Some(material) = &**variant_material else {
+        return;
+    };
+
+    debug!("replacing materials for `{}`", ready.entity);
+    let mut iter = material_handles
+        .iter_many_mut(iter::once(ready.entity).chain(children.iter_descendants(ready.entity)));
+    while let Some(mut material_handle) = iter.fetch_next() {
+        material_handle.0 = material.clone();
+    }
+
+    if let Some(&tint) = tint {
+        // Re-apply to the replaced materials.
+        commands.entity(ready.entity).insert(tint);
+    }
+}
+
+/// Loads the asset and the material for the variant from the manifest.
+///
+/// Triggers respawn only if the look has changed.
+pub(super) fn apply_variant(
+    asset_server: &AssetServer,
+    manifest: &ObjectManifest,
+    variant: &str,
+    asset_root: &mut Mut<WorldAssetRoot>,
+    variant_material: &mut Mut<VariantMaterial>,
+) {
+    let asset = asset_server.load(manifest.variant_asset(variant).clone());
+    let material = VariantMaterial(
+        manifest
+            .variant_material(variant)
+            .map(|path| asset_server.load(path.clone())),
+    );
+
+    if asset_root.0 != asset || **variant_material != material {
+        debug!("loading variant '{variant}' with asset `{asset:?}`");
+        asset_root.0 = asset;
+        **variant_material = material;
+    }
+}
+
+/// Material that replaces all materials of the spawned asset.
+#[derive(Component, Default, Clone, PartialEq, Deref)]
+pub(super) struct VariantMaterial(Option<Handle<StandardMaterial>>);
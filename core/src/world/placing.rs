@@ -1,5 +1,5 @@
 pub(super) mod intersection;
-mod tint;
+pub(super) mod tint;
 
 use bevy::{
     color::palettes::{css::WHITE, tailwind::RED_500},
@@ -1,16 +1,27 @@
-use bevy::prelude::*;
-use bevy_replicon::prelude::*;
+use std::str::FromStr;
+
+use bevy::{ecs::entity::MapEntities, prelude::*};
+use bevy_replicon::{prelude::*, shared::backend::connected_client::NetworkId};
 use serde::{Deserialize, Serialize};
 
-use crate::state::GameState;
+use crate::{
+    network::PlayerId,
+    replay::{ReplayAppExt, ReplayableEvent},
+    state::GameState,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_client_event::<SetPaused>(Channel::Ordered)
+    app.init_resource::<SpeedPolicy>()
+        .add_client_event::<SetPaused>(Channel::Ordered)
         .add_client_event::<SetSpeed>(Channel::Ordered)
+        .record_client_event::<SetPaused>()
+        .record_client_event::<SetSpeed>()
         .replicate_resource::<GameSpeed>()
         .replicate_resource::<Paused>()
+        .replicate_resource::<SpeedVotes>()
         .add_observer(set_paused)
         .add_observer(set_speed)
+        .add_observer(remove_vote)
         .add_observer(apply_speed)
         .add_observer(apply_paused)
         .add_systems(
@@ -22,15 +33,70 @@ pub(super) fn plugin(app: &mut App) {
 fn spawn(mut commands: Commands) {
     commands.insert_resource(GameSpeed::default());
     commands.insert_resource(Paused::default());
+    commands.insert_resource(SpeedVotes::default());
 }
 
-fn set_paused(paused: On<FromClient<SetPaused>>, mut commands: Commands) {
-    commands.insert_resource(Paused(***paused));
+fn set_paused(
+    paused: On<FromClient<SetPaused>>,
+    mut commands: Commands,
+    policy: Res<SpeedPolicy>,
+    game_speed: Res<GameSpeed>,
+    current_paused: Res<Paused>,
+    votes: Res<SpeedVotes>,
+    clients: Query<&NetworkId>,
+) {
+    let voter = PlayerId::new(paused.client_id, &clients);
+    let mut votes = votes.clone();
+    let vote = votes.get_or_insert(voter, *game_speed, **current_paused);
+    vote.paused = ***paused;
+
+    debug!("`{voter}` votes for paused `{}`", vote.paused);
+    apply_votes(&mut commands, *policy, votes);
 }
 
-fn set_speed(speed: On<FromClient<SetSpeed>>, mut commands: Commands) {
-    commands.insert_resource(***speed);
-    commands.insert_resource_if_neq(Paused(false));
+fn set_speed(
+    speed: On<FromClient<SetSpeed>>,
+    mut commands: Commands,
+    policy: Res<SpeedPolicy>,
+    votes: Res<SpeedVotes>,
+    clients: Query<&NetworkId>,
+) {
+    let voter = PlayerId::new(speed.client_id, &clients);
+    let mut votes = votes.clone();
+    let vote = votes.get_or_insert(voter, ***speed, false);
+    vote.speed = ***speed;
+    vote.paused = false;
+
+    debug!("`{voter}` votes for `{:?}`", vote.speed);
+    apply_votes(&mut commands, *policy, votes);
+}
+
+fn remove_vote(
+    remove: On<Remove, NetworkId>,
+    mut commands: Commands,
+    policy: Res<SpeedPolicy>,
+    votes: Option<Res<SpeedVotes>>,
+    clients: Query<&NetworkId>,
+) {
+    let Some(votes) = votes else {
+        return;
+    };
+
+    let voter = PlayerId::new(ClientId::Client(remove.entity), &clients);
+    let mut votes = votes.clone();
+    if votes.remove(voter) {
+        debug!("removing vote from disconnected `{voter}`");
+        apply_votes(&mut commands, *policy, votes);
+    }
+}
+
+/// Resolves speed and pause state from votes and inserts everything as resources.
+fn apply_votes(commands: &mut Commands, policy: SpeedPolicy, votes: SpeedVotes) {
+    if let Some((speed, paused)) = policy.resolve(&votes) {
+        commands.insert_resource_if_neq(speed);
+        commands.insert_resource_if_neq(Paused(paused));
+    }
+    commands.insert_resource(votes);
 }
 
 fn apply_paused(
@@ -60,9 +126,13 @@ fn apply_speed(
     }
 }
 
-#[derive(Event, Deref, Serialize, Deserialize)]
+#[derive(Event, Deref, Serialize, Deserialize, MapEntities)]
 pub struct SetPaused(pub bool);
 
+impl ReplayableEvent for SetPaused {
+    fn mark_replayed(&mut self) {}
+}
+
 // Replicated, but not serialized since the value is not reflected.
 #[derive(Resource, Deref, DerefMut, Serialize, Deserialize, PartialEq)]
 #[require(DespawnOnExit::<_>(GameState::World))]
@@ -79,10 +149,27 @@ impl Default for Paused {
     }
 }
 
-#[derive(Event, Deref, Serialize, Deserialize)]
+#[derive(Event, Deref, Serialize, Deserialize, MapEntities)]
 pub struct SetSpeed(pub GameSpeed);
 
-#[derive(Resource, Reflect, Default, Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+impl ReplayableEvent for SetSpeed {
+    fn mark_replayed(&mut self) {}
+}
+
+#[derive(
+    Resource,
+    Reflect,
+    Default,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+)]
 #[require(DespawnOnExit::<_>(GameState::World))]
 #[component(immutable)]
 pub enum GameSpeed {
@@ -93,6 +180,8 @@ pub enum GameSpeed {
 }
 
 impl GameSpeed {
+    const ALL: [GameSpeed; 3] = [GameSpeed::Normal, GameSpeed::Fast, GameSpeed::Ultra];
+
     fn multiplier(&self) -> f32 {
         match self {
             GameSpeed::Normal => 1.0,
@@ -101,3 +190,186 @@ impl GameSpeed {
         }
     }
 }
+
+/// Defines how the host combines speed and pause requests from players.
+#[derive(Resource, Default, Debug, PartialEq, Clone, Copy)]
+pub enum SpeedPolicy {
+    /// Only requests from the host are applied.
+    #[default]
+    HostOnly,
+    /// The speed requested by most players wins, ties are resolved in favor of the slower one.
+    ///
+    /// The game pauses only if more than half of the players requested it.
+    Majority,
+    /// The slowest requested speed wins and the game pauses if anyone requested it.
+    Slowest,
+}
+
+impl SpeedPolicy {
+    /// Returns the resulting speed and pause state or [`None`] if nothing should change.
+    fn resolve(self, votes: &SpeedVotes) -> Option<(GameSpeed, bool)> {
+        match self {
+            SpeedPolicy::HostOnly => votes
+                .iter()
+                .find(|vote| vote.voter == PlayerId::Host)
+                .map(|vote| (vote.speed, vote.paused)),
+            SpeedPolicy::Majority => {
+                if votes.is_empty() {
+                    return None;
+                }
+
+                let mut speed = GameSpeed::default();
+                let mut max_count = 0;
+                for candidate in GameSpeed::ALL {
+                    let count = votes.iter().filter(|v| v.speed == candidate).count();
+                    if count > max_count {
+                        speed = candidate;
+                        max_count = count;
+                    }
+                }
+
+                let paused_count = votes.iter().filter(|v| v.paused).count();
+
+                Some((speed, paused_count * 2 > votes.len()))
+            }
+            SpeedPolicy::Slowest => {
+                let speed = votes.iter().map(|v| v.speed).min()?;
+                let paused = votes.iter().any(|v| v.paused);
+
+                Some((speed, paused))
+            }
+        }
+    }
+}
+
+impl FromStr for SpeedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "host-only" => Ok(SpeedPolicy::HostOnly),
+            "majority" => Ok(SpeedPolicy::Majority),
+            "slowest" => Ok(SpeedPolicy::Slowest),
+            _ => Err(format!(
+                "unknown speed policy '{s}', expected 'host-only', 'majority' or 'slowest'"
+            )),
+        }
+    }
+}
+
+/// Speed and pause state requested by each player.
+///
+/// Replicated to let clients display what others want.
+#[derive(Resource, Deref, Default, Serialize, Deserialize, Clone)]
+#[require(DespawnOnExit::<_>(GameState::World))]
+#[component(immutable)]
+pub struct SpeedVotes(Vec<SpeedVote>);
+
+impl SpeedVotes {
+    fn get_or_insert(&mut self, voter: PlayerId, speed: GameSpeed, paused: bool) -> &mut SpeedVote {
+        let index = match self.0.iter().position(|vote| vote.voter == voter) {
+            Some(index) => index,
+            None => {
+                self.0.push(SpeedVote {
+                    voter,
+                    speed,
+                    paused,
+                });
+                self.0.len() - 1
+            }
+        };
+
+        &mut self.0[index]
+    }
+
+    fn remove(&mut self, voter: PlayerId) -> bool {
+        let len = self.0.len();
+        self.0.retain(|vote| vote.voter != voter);
+        self.0.len() != len
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct SpeedVote {
+    pub voter: PlayerId,
+    pub speed: GameSpeed,
+    pub paused: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn host_only() {
+        let votes = speed_votes([
+            (PlayerId::Client(1), GameSpeed::Ultra, true),
+            (PlayerId::Host, GameSpeed::Fast, false),
+        ]);
+        assert_eq!(
+            SpeedPolicy::HostOnly.resolve(&votes),
+            Some((GameSpeed::Fast, false))
+        );
+
+        let votes = speed_votes([(PlayerId::Client(1), GameSpeed::Ultra, true)]);
+        assert_eq!(
+            SpeedPolicy::HostOnly.resolve(&votes),
+            None,
+            "clients alone shouldn't change anything"
+        );
+    }
+
+    #[test]
+    fn majority() {
+        let votes = speed_votes([
+            (PlayerId::Host, GameSpeed::Normal, false),
+            (PlayerId::Client(1), GameSpeed::Ultra, true),
+            (PlayerId::Client(2), GameSpeed::Ultra, true),
+        ]);
+        assert_eq!(
+            SpeedPolicy::Majority.resolve(&votes),
+            Some((GameSpeed::Ultra, true))
+        );
+
+        let votes = speed_votes([
+            (PlayerId::Host, GameSpeed::Ultra, true),
+            (PlayerId::Client(1), GameSpeed::Fast, false),
+        ]);
+        assert_eq!(
+            SpeedPolicy::Majority.resolve(&votes),
+            Some((GameSpeed::Fast, false)),
+            "ties should prefer the slower speed and half of the votes shouldn't pause"
+        );
+
+        assert_eq!(SpeedPolicy::Majority.resolve(&SpeedVotes::default()), None);
+    }
+
+    #[test]
+    fn slowest() {
+        let votes = speed_votes([
+            (PlayerId::Host, GameSpeed::Ultra, false),
+            (PlayerId::Client(1), GameSpeed::Fast, false),
+            (PlayerId::Client(2), GameSpeed::Ultra, true),
+        ]);
+        assert_eq!(
+            SpeedPolicy::Slowest.resolve(&votes),
+            Some((GameSpeed::Fast, true))
+        );
+
+        assert_eq!(SpeedPolicy::Slowest.resolve(&SpeedVotes::default()), None);
+    }
+
+    fn speed_votes<const N: usize>(votes: [(PlayerId, GameSpeed, bool); N]) -> SpeedVotes {
+        SpeedVotes(
+            votes
+                .map(|(voter, speed, paused)| SpeedVote {
+                    voter,
+                    speed,
+                    paused,
+                })
+                .into(),
+        )
+    }
+}
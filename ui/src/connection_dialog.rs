@@ -19,8 +19,8 @@ fn spawn(mut commands: Commands) {
         DespawnOnEnter(GameState::World),
         DespawnOnEnter(ClientState::Disconnected),
         Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
-            parent.spawn(dialog_title("Connecting to server"));
-            parent.spawn(dialog_close_button("Cancel")).observe(
+            parent.spawn(dialog_title("connection.title"));
+            parent.spawn(dialog_close_button("dialog.cancel")).observe(
                 |_on: On<Fire<Activate>>, mut commands: Commands| commands.trigger(Disconnect),
             );
         })),
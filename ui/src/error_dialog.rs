@@ -1,10 +1,10 @@
 use bevy::prelude::*;
-use simgine_core::error_event::ErrorEvent;
+use simgine_core::{error_event::ErrorEvent, localization::Localization, undo::revision::Conflict};
 
 use crate::widget::dialog::{dialog, dialog_close_button, dialog_text, dialog_title};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_observer(spawn);
+    app.add_observer(spawn).add_observer(spawn_conflict);
 }
 
 fn spawn(error: On<ErrorEvent>, mut commands: Commands) {
@@ -12,9 +12,21 @@ fn spawn(error: On<ErrorEvent>, mut commands: Commands) {
     commands.spawn((
         dialog(),
         children![
-            dialog_title("Error"),
+            dialog_title("error.title"),
             dialog_text(message),
-            dialog_close_button("Ok"),
+            dialog_close_button("dialog.ok"),
+        ],
+    ));
+}
+
+fn spawn_conflict(conflict: On<Conflict>, mut commands: Commands, localization: Res<Localization>) {
+    let message = localization.format("conflict.message", &[("editor", &conflict.editor)]);
+    commands.spawn((
+        dialog(),
+        children![
+            dialog_title("conflict.title"),
+            dialog_text(message),
+            dialog_close_button("dialog.ok"),
         ],
     ));
 }
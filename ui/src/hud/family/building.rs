@@ -1,18 +1,24 @@
 mod objects;
 mod toolbar;
 
+use std::fmt::Write;
+
 use bevy::{ecs::relationship::RelatedSpawner, prelude::*};
-use simgine_core::state::{BuildingMode, FamilyMode};
+use simgine_core::{
+    state::{BuildingMode, FamilyMode},
+    world::funds::Funds,
+};
 
 use crate::widget::{
     button::{
         exclusive_group::ExclusiveGroup, icon::ButtonIcon, style::ButtonStyle, toggled::Toggled,
     },
-    theme::SCREEN_OFFSET,
+    theme::{LARGE_TEXT, SCREEN_OFFSET},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(objects::plugin)
+    app.add_plugins((objects::plugin, toolbar::plugin))
+        .add_observer(update_funds)
         .add_systems(OnEnter(FamilyMode::Building), spawn);
 }
 
@@ -27,6 +33,12 @@ fn spawn(mut commands: Commands) {
         },
         DespawnOnExit(FamilyMode::Building),
         children![
+            (
+                Name::new("Funds"),
+                FundsLabel,
+                Text::default(),
+                TextFont::from_font_size(LARGE_TEXT)
+            ),
             (
                 Node::default(),
                 ExclusiveGroup::default(),
@@ -59,3 +71,15 @@ fn spawn(mut commands: Commands) {
 
     commands.spawn(toolbar::toolbar_node());
 }
+
+fn update_funds(
+    _on: On<Insert, (Funds, FundsLabel)>,
+    funds: Res<Funds>,
+    mut text: Single<&mut Text, With<FundsLabel>>,
+) {
+    text.clear();
+    write!(text, "{}", *funds).unwrap();
+}
+
+#[derive(Component)]
+struct FundsLabel;
@@ -1,6 +1,14 @@
-use bevy::prelude::*;
+use std::{cmp::Ordering, fmt::Write};
+
+use bevy::{ecs::relationship::RelatedSpawner, prelude::*, text::EditableText};
 use simgine_core::{
-    asset_manifest::object::ObjectManifest, state::BuildingMode, world::object::placing::spawning,
+    asset_manifest::{
+        category::{CategoriesExt, CategoryManifest},
+        object::ObjectManifest,
+    },
+    localization::Localization,
+    state::BuildingMode,
+    world::{funds::Funds, object::placing::spawning},
 };
 
 use crate::{
@@ -9,32 +17,214 @@ use crate::{
         button::{
             exclusive_group::ExclusiveGroup, icon::ButtonIcon, style::ButtonStyle, toggled::Toggled,
         },
+        localized_text::LocalizedText,
+        text_edit::text_edit,
         theme::{
-            INNER_RADIUS, OUTER_RADIUS, PREVIEW_COLUMNS, PREVIEW_GAP, PREVIEW_HEIGHT,
-            PREVIEW_WIDTH, RADIUS_GAP, SHADOW,
+            GAP, INNER_RADIUS, OUTER_RADIUS, PREVIEW_COLUMNS, PREVIEW_GAP, PREVIEW_HEIGHT,
+            PREVIEW_WIDTH, RADIUS_GAP, SHADOW, SMALL_TEXT,
         },
+        tooltip::Tooltip,
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_observer(spawn_grid_buttons)
+    app.init_resource::<CatalogFilter>()
+        .add_observer(spawn_category_buttons)
+        .add_systems(
+            Update,
+            (reload_categories, (update_search, update_grid).chain()),
+        )
         .add_systems(OnEnter(BuildingMode::Objects), show)
         .add_systems(OnExit(BuildingMode::Objects), hide);
 }
 
-fn spawn_grid_buttons(
-    add: On<Add, ObjectsGrid>,
+/// Spawns root category buttons, replacing the previous ones.
+fn spawn_category_buttons(
+    insert: On<Insert, CategoriesNode>,
+    mut commands: Commands,
+    mut filter: ResMut<CatalogFilter>,
+    localization: Res<Localization>,
+    categories: Res<Assets<CategoryManifest>>,
+    subcategories_nodes: Query<Entity, With<SubcategoriesNode>>,
+) {
+    debug!("spawning category buttons");
+    *filter = Default::default();
+
+    for entity in &subcategories_nodes {
+        commands.entity(entity).despawn_related::<Children>();
+    }
+
+    commands
+        .entity(insert.entity)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    ButtonIcon::new("base/ui/icons/all_objects.png"),
+                    Toggled(true),
+                    RootButton(None),
+                ))
+                .observe(select_root);
+            for category in categories.children(None) {
+                category_button(parent, category, &localization)
+                    .insert(RootButton(Some(category.id.clone())))
+                    .observe(select_root);
+            }
+        });
+}
+
+/// Respawns category buttons when categories are modified on disk.
+fn reload_categories(
+    mut commands: Commands,
+    mut asset_events: MessageReader<AssetEvent<CategoryManifest>>,
+    categories_node: Single<Entity, With<CategoriesNode>>,
+) {
+    if asset_events.read().count() == 0 {
+        return;
+    }
+
+    debug!("reloading categories");
+    commands.entity(*categories_node).insert(CategoriesNode);
+}
+
+fn select_root(
+    click: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut filter: ResMut<CatalogFilter>,
+    localization: Res<Localization>,
+    categories: Res<Assets<CategoryManifest>>,
+    buttons: Query<&RootButton>,
+    subcategories_node: Single<Entity, With<SubcategoriesNode>>,
+) {
+    let Ok(button) = buttons.get(click.entity) else {
+        return;
+    };
+
+    debug!("selecting root category {:?}", **button);
+    filter.category = (**button).clone();
+    filter.page = 0;
+
+    let mut subcategories = commands.entity(*subcategories_node);
+    subcategories.despawn_related::<Children>();
+    if let Some(id) = &**button {
+        subcategories.with_children(|parent| {
+            for category in categories.children(Some(id)) {
+                category_button(parent, category, &localization)
+                    .insert(SubcategoryButton(category.id.clone()))
+                    .observe(select_subcategory);
+            }
+        });
+    }
+}
+
+fn select_subcategory(
+    click: On<Pointer<Click>>,
+    mut filter: ResMut<CatalogFilter>,
+    buttons: Query<&SubcategoryButton>,
+) {
+    let Ok(button) = buttons.get(click.entity) else {
+        return;
+    };
+
+    debug!("selecting subcategory '{}'", **button);
+    filter.category = Some((**button).clone());
+    filter.page = 0;
+}
+
+fn category_button<'a>(
+    parent: &'a mut ChildSpawnerCommands,
+    category: &CategoryManifest,
+    localization: &Localization,
+) -> EntityCommands<'a> {
+    let mut button = parent.spawn((Button, Toggled(false)));
+    match &category.icon {
+        Some(icon) => button.insert(ButtonIcon::new(icon.clone())),
+        None => button.insert((
+            ButtonStyle::default(),
+            Text::new(localization.get(&category.name)),
+            TextFont::from_font_size(SMALL_TEXT),
+        )),
+    };
+
+    button
+}
+
+fn update_search(
+    mut filter: ResMut<CatalogFilter>,
+    search_edit: Single<&EditableText, (With<SearchEdit>, Changed<EditableText>)>,
+) {
+    let search = search_edit.value().to_string().to_lowercase();
+    if filter.search != search {
+        debug!("searching for '{search}'");
+        filter.search = search;
+        filter.page = 0;
+    }
+}
+
+/// Respawns buttons for the current page when the filter or manifests change.
+///
+/// Only buttons on the current page are spawned to avoid rendering all thumbnails.
+fn update_grid(
     mut commands: Commands,
+    mut asset_events: MessageReader<AssetEvent<ObjectManifest>>,
+    mut filter: ResMut<CatalogFilter>,
+    localization: Res<Localization>,
     objects: Res<Assets<ObjectManifest>>,
+    categories: Res<Assets<CategoryManifest>>,
+    grid: Single<Entity, With<ObjectsGrid>>,
+    mut page_label: Single<&mut Text, With<PageLabel>>,
 ) {
-    debug!("spawning grid buttons");
+    // Also respawn on reload to pick up the changes.
+    let reloaded = asset_events.read().count() > 0;
+    if !reloaded && !filter.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    let mut matching: Vec<_> = objects
+        .iter()
+        .filter(|(_, manifest)| filter.matches(manifest, &categories, &localization))
+        .collect();
+    matching.sort_by(|(_, a), (_, b)| filter.sort.compare(a, b, &localization));
+
+    let pages = matching.len().div_ceil(PAGE_SIZE).max(1);
+    if filter.page >= pages {
+        filter.bypass_change_detection().page = pages - 1;
+    }
+
+    debug!(
+        "showing page {} out of {pages} with {} matching objects",
+        filter.page + 1,
+        matching.len()
+    );
+
+    page_label.clear();
+    write!(page_label, "{}/{pages}", filter.page + 1).unwrap();
+
+    commands.entity(*grid).despawn_related::<Children>();
+    commands.entity(*grid).with_children(|parent| {
+        for &(id, manifest) in matching
+            .iter()
+            .skip(filter.page * PAGE_SIZE)
+            .take(PAGE_SIZE)
+        {
+            let info = &manifest.info;
+            let tooltip = localization.format(
+                "catalog.info",
+                &[
+                    ("name", &localization.get(&info.name)),
+                    ("author", &info.author),
+                    ("license", &info.license),
+                ],
+            );
 
-    commands.entity(add.entity).with_children(|parent| {
-        for (id, _) in objects.iter() {
             parent
                 .spawn((
                     Button,
+                    Tooltip(tooltip),
                     Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
                         padding: RADIUS_GAP,
                         border_radius: OUTER_RADIUS,
                         ..Default::default()
@@ -42,15 +232,21 @@ fn spawn_grid_buttons(
                     BoxShadow::from(SHADOW),
                     ButtonStyle::default(),
                     Toggled(false),
-                    children![(
-                        Thumbnail(id),
-                        Node {
-                            height: PREVIEW_HEIGHT,
-                            width: PREVIEW_WIDTH,
-                            border_radius: INNER_RADIUS,
-                            ..Default::default()
-                        },
-                    )],
+                    children![
+                        (
+                            Thumbnail(id),
+                            Node {
+                                height: PREVIEW_HEIGHT,
+                                width: PREVIEW_WIDTH,
+                                border_radius: INNER_RADIUS,
+                                ..Default::default()
+                            },
+                        ),
+                        (
+                            Text::new(Funds(manifest.price).to_string()),
+                            TextFont::from_font_size(SMALL_TEXT),
+                        )
+                    ],
                 ))
                 .observe(move |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.spawn(spawning::spawning_object(id));
@@ -71,23 +267,48 @@ pub(super) fn objects_node() -> impl Bundle {
     (
         Node {
             flex_direction: FlexDirection::Column,
+            row_gap: PREVIEW_GAP,
             ..Default::default()
         },
         ObjectsNode,
-        children![
-            (
+        Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
+            parent.spawn((Node::default(), ExclusiveGroup::default(), CategoriesNode));
+            parent.spawn((
                 Node::default(),
                 ExclusiveGroup::default(),
-                children![
-                    (
-                        Button,
-                        ButtonIcon::new("base/ui/icons/all_objects.png"),
-                        Toggled(true),
-                    ),
-                    (Button, ButtonIcon::new("base/ui/icons/furniture.png"))
-                ],
-            ),
-            (
+                SubcategoriesNode,
+            ));
+            parent.spawn((SearchEdit, text_edit("")));
+            parent.spawn((
+                Node {
+                    column_gap: GAP,
+                    ..Default::default()
+                },
+                ExclusiveGroup::default(),
+                Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
+                    for (sort, key) in [
+                        (CatalogSort::Name, "catalog.sort_name"),
+                        (CatalogSort::Price, "catalog.sort_price"),
+                    ] {
+                        parent
+                            .spawn((
+                                Button,
+                                ButtonStyle::default(),
+                                Toggled(sort == CatalogSort::default()),
+                                LocalizedText(key),
+                                TextFont::from_font_size(SMALL_TEXT),
+                            ))
+                            .observe(
+                                move |_on: On<Pointer<Click>>,
+                                      mut filter: ResMut<CatalogFilter>| {
+                                    filter.sort = sort;
+                                    filter.page = 0;
+                                },
+                            );
+                    }
+                })),
+            ));
+            parent.spawn((
                 ExclusiveGroup::default(),
                 ObjectsGrid,
                 Node {
@@ -97,8 +318,43 @@ pub(super) fn objects_node() -> impl Bundle {
                     grid_template_columns: vec![GridTrack::auto(); PREVIEW_COLUMNS],
                     ..Default::default()
                 },
-            )
-        ],
+            ));
+            parent.spawn((
+                Node {
+                    align_self: AlignSelf::Center,
+                    align_items: AlignItems::Center,
+                    column_gap: GAP,
+                    ..Default::default()
+                },
+                Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
+                    parent.spawn(page_button("<")).observe(
+                        |_on: On<Pointer<Click>>, mut filter: ResMut<CatalogFilter>| {
+                            filter.page = filter.page.saturating_sub(1);
+                        },
+                    );
+                    parent.spawn((
+                        PageLabel,
+                        Text::default(),
+                        TextFont::from_font_size(SMALL_TEXT),
+                    ));
+                    parent.spawn(page_button(">")).observe(
+                        |_on: On<Pointer<Click>>, mut filter: ResMut<CatalogFilter>| {
+                            // Clamped on grid update.
+                            filter.page += 1;
+                        },
+                    );
+                })),
+            ));
+        })),
+    )
+}
+
+fn page_button(text: &str) -> impl Bundle {
+    (
+        Button,
+        ButtonStyle::default(),
+        Text::new(text),
+        TextFont::from_font_size(SMALL_TEXT),
     )
 }
 
@@ -107,3 +363,87 @@ struct ObjectsNode;
 
 #[derive(Component)]
 struct ObjectsGrid;
+
+#[derive(Component)]
+struct CategoriesNode;
+
+#[derive(Component)]
+struct SubcategoriesNode;
+
+/// Root category of the button or [`None`] for all objects.
+#[derive(Component, Deref)]
+struct RootButton(Option<String>);
+
+#[derive(Component, Deref)]
+struct SubcategoryButton(String);
+
+#[derive(Component)]
+struct SearchEdit;
+
+#[derive(Component)]
+struct PageLabel;
+
+const PAGE_SIZE: usize = PREVIEW_COLUMNS * 4;
+
+/// Selected category, search and sorting for the catalog.
+#[derive(Resource, Default)]
+struct CatalogFilter {
+    /// Category ID or [`None`] to display all objects.
+    category: Option<String>,
+
+    /// Lowercase text to search in translated names and tags.
+    search: String,
+    sort: CatalogSort,
+    page: usize,
+}
+
+impl CatalogFilter {
+    fn matches(
+        &self,
+        manifest: &ObjectManifest,
+        categories: &Assets<CategoryManifest>,
+        localization: &Localization,
+    ) -> bool {
+        let in_category = self.category.as_ref().is_none_or(|selected| {
+            manifest
+                .categories
+                .iter()
+                .any(|category| categories.is_within(category, selected))
+        });
+
+        in_category
+            && (self.search.is_empty()
+                || localization
+                    .get(&manifest.info.name)
+                    .to_lowercase()
+                    .contains(&self.search)
+                || manifest
+                    .tags
+                    .iter()
+                    .any(|tag| localization.get(tag).to_lowercase().contains(&self.search)))
+    }
+}
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum CatalogSort {
+    #[default]
+    Name,
+    Price,
+}
+
+impl CatalogSort {
+    fn compare(
+        self,
+        a: &ObjectManifest,
+        b: &ObjectManifest,
+        localization: &Localization,
+    ) -> Ordering {
+        let by_name = localization
+            .get(&a.info.name)
+            .cmp(localization.get(&b.info.name));
+        match self {
+            CatalogSort::Name => by_name,
+            CatalogSort::Price => a.price.cmp(&b.price).then(by_name),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::{ecs::relationship::RelatedSpawner, prelude::*};
 use bevy_enhanced_input::prelude::*;
-use simgine_core::{state::FamilyMode, undo::HistoryCommands};
+use simgine_core::{
+    state::FamilyMode,
+    undo::{HistoryCommands, history::CommandHistory},
+};
 
 use crate::{
     button_bindings,
@@ -9,11 +12,82 @@ use crate::{
             action::{Activate, ButtonContext},
             icon::ButtonIcon,
             style::ButtonStyle,
+            toggled::Toggled,
         },
-        theme::SCREEN_OFFSET,
+        theme::{INACTIVE, SCREEN_OFFSET, SMALL_TEXT},
     },
 };
 
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_history.run_if(in_state(FamilyMode::Building)),
+    );
+}
+
+/// Rebuilds history entries when the history changes.
+///
+/// Entries are listed from the oldest to the newest, with the current point toggled.
+/// Clicking an entry undoes or redoes everything after it.
+fn update_history(
+    mut commands: Commands,
+    history: Res<CommandHistory>,
+    panel: Single<(Entity, Ref<HistoryPanel>)>,
+) {
+    let (panel, marker) = *panel;
+    if !history.is_changed() && !marker.is_added() {
+        return;
+    }
+
+    debug!("updating history panel");
+    commands
+        .entity(panel)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            let undo_len = history.undo_labels().len();
+            for (index, label) in history.undo_labels().enumerate() {
+                let steps = undo_len - index - 1;
+                parent
+                    .spawn((
+                        history_entry(label, ButtonStyle::WHITE),
+                        Toggled(steps == 0),
+                    ))
+                    .observe(
+                        move |_on: On<Pointer<Click>>, mut commands: HistoryCommands| {
+                            commands.undo_steps(steps);
+                        },
+                    );
+            }
+
+            for (index, label) in history.redo_labels().enumerate() {
+                parent
+                    .spawn(history_entry(label, ButtonStyle::BLACK))
+                    .observe(
+                        move |_on: On<Pointer<Click>>, mut commands: HistoryCommands| {
+                            commands.redo_steps(index + 1);
+                        },
+                    );
+            }
+
+            for label in history.pending_labels() {
+                parent.spawn((
+                    Text::new(format!("{label}...")),
+                    TextFont::from_font_size(SMALL_TEXT),
+                    TextColor(INACTIVE.into()),
+                ));
+            }
+        });
+}
+
+fn history_entry(label: &str, style: ButtonStyle) -> impl Bundle {
+    (
+        Button,
+        Text::new(label),
+        TextFont::from_font_size(SMALL_TEXT),
+        style,
+    )
+}
+
 pub(super) fn toolbar_node() -> impl Bundle {
     (
         Node {
@@ -52,6 +126,17 @@ pub(super) fn toolbar_node() -> impl Bundle {
                 .observe(|_on: On<Fire<Activate>>, mut commands: HistoryCommands| {
                     commands.redo();
                 });
+            parent.spawn((
+                Name::new("History panel"),
+                HistoryPanel,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                },
+            ));
         })),
     )
 }
+
+#[derive(Component)]
+struct HistoryPanel;
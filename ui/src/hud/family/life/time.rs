@@ -11,7 +11,7 @@ use simgine_core::{
     state::FamilyMode,
     world::time::{
         Clock, Weekday,
-        speed::{GameSpeed, Paused, SetPaused, SetSpeed},
+        speed::{GameSpeed, Paused, SetPaused, SetSpeed, SpeedVotes},
     },
 };
 
@@ -34,6 +34,7 @@ pub(super) fn plugin(app: &mut App) {
         .add_observer(update_clock)
         .add_observer(update_pause_button)
         .add_observer(update_speed_buttons)
+        .add_observer(update_votes)
         .add_systems(OnEnter(FamilyMode::Life), spawn);
 }
 
@@ -112,6 +113,12 @@ fn spawn(mut commands: Commands) {
                         .insert(SpeedNode); // Workaround to react on insertion after hierarchy spawn.
                 })),
             ),
+            (
+                Name::new("Speed votes"),
+                VotesLabel,
+                Text::default(),
+                TextFont::from_font_size(SMALL_TEXT)
+            ),
         ],
     ));
 }
@@ -159,6 +166,29 @@ fn update_speed_buttons(
     }
 }
 
+fn update_votes(
+    _on: On<Insert, (SpeedVotes, VotesLabel)>,
+    votes: Res<SpeedVotes>,
+    mut text: Single<&mut Text, With<VotesLabel>>,
+) {
+    text.clear();
+
+    // Show votes only when there is someone to compare with.
+    if votes.len() <= 1 {
+        return;
+    }
+
+    for vote in votes.iter() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        write!(text, "{}: {:?}", vote.voter, vote.speed).unwrap();
+        if vote.paused {
+            text.push_str(" (paused)");
+        }
+    }
+}
+
 fn speed_button(speed: GameSpeed) -> impl Bundle {
     let (icon, key) = match speed {
         GameSpeed::Normal => ("base/ui/icons/normal_speed.png", KeyCode::Digit1),
@@ -184,6 +214,9 @@ struct WeekdayLabel;
 #[derive(Component)]
 struct ClockLabel;
 
+#[derive(Component)]
+struct VotesLabel;
+
 #[derive(Component)]
 struct PauseButton;
 
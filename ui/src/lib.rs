@@ -1,6 +1,7 @@
 mod connection_dialog;
 mod error_dialog;
 mod hud;
+mod loading_screen;
 mod menu;
 mod thumbnail;
 mod widget;
@@ -15,6 +16,7 @@ impl Plugin for SimgineUiPlugin {
             connection_dialog::plugin,
             error_dialog::plugin,
             hud::plugin,
+            loading_screen::plugin,
             menu::plugin,
             thumbnail::plugin,
             widget::plugin,
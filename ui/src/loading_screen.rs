@@ -0,0 +1,59 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use simgine_core::{asset_manifest::ManifestsProgress, state::GameState};
+
+use crate::widget::theme::HUGE_TEXT;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::ManifestsLoading), spawn)
+        .add_systems(
+            Update,
+            update_progress
+                .run_if(resource_changed::<ManifestsProgress>)
+                .run_if(in_state(GameState::ManifestsLoading)),
+        );
+}
+
+fn spawn(mut commands: Commands) {
+    debug!("spawning loading screen");
+    commands.spawn((
+        DespawnOnExit(GameState::ManifestsLoading),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        // Not localized since locales are loaded as part of the manifests.
+        children![(
+            ProgressText,
+            Text::new("Searching for manifests"),
+            TextFont::from_font_size(HUGE_TEXT),
+        )],
+    ));
+}
+
+fn update_progress(
+    progress: Res<ManifestsProgress>,
+    mut text: Single<&mut Text, With<ProgressText>>,
+) {
+    let Some(total) = progress.total else {
+        return;
+    };
+
+    text.clear();
+    write!(
+        text.0,
+        "Loading manifests: {}/{total}",
+        progress.loaded + progress.failed
+    )
+    .unwrap();
+    if progress.failed > 0 {
+        write!(text.0, " ({} failed)", progress.failed).unwrap();
+    }
+}
+
+#[derive(Component)]
+struct ProgressText;
@@ -17,4 +17,5 @@ enum MenuState {
     #[default]
     MainMenu,
     WorldBrowser,
+    Credits,
 }
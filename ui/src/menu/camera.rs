@@ -13,7 +13,8 @@ fn spawn(mut commands: Commands) {
         Name::new("Menu camera"),
         Camera2d,
         Camera {
-            is_active: false,
+            // Active initially to display the loading screen.
+            is_active: true,
             order: -1,
             ..Default::default()
         },
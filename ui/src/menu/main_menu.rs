@@ -1,24 +1,36 @@
+mod credits;
 mod world_browser;
 
 use bevy::{ecs::relationship::RelatedSpawner, prelude::*};
-use simgine_core::state::GameState;
+use simgine_core::{
+    localization::Localization,
+    settings::{SaveSettings, Settings},
+    state::GameState,
+};
 
 use crate::{
     menu::MenuState,
     widget::{
-        button::style::ButtonStyle,
-        theme::{GAP, HUGE_TEXT, SCREEN_OFFSET},
+        button::{exclusive_group::ExclusiveGroup, style::ButtonStyle, toggled::Toggled},
+        localized_text::LocalizedText,
+        theme::{GAP, HUGE_TEXT, NORMAL_TEXT, SCREEN_OFFSET},
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(world_browser::plugin)
+    app.add_plugins((credits::plugin, world_browser::plugin))
         .add_systems(OnEnter(MenuState::MainMenu), spawn);
 }
 
-fn spawn(mut commands: Commands) {
+fn spawn(mut commands: Commands, localization: Res<Localization>) {
     info!("entering main menu");
 
+    let language = localization.language().to_string();
+    let languages: Vec<_> = localization
+        .languages()
+        .map(|(code, name)| (code.to_string(), name.to_string()))
+        .collect();
+
     commands.spawn((
         DespawnOnExit(MenuState::MainMenu),
         Node {
@@ -29,30 +41,64 @@ fn spawn(mut commands: Commands) {
             ..Default::default()
         },
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-            parent.spawn(button("Play")).observe(
+            parent.spawn(button("main_menu.play")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.set_state(MenuState::WorldBrowser)
                 },
             );
-            parent.spawn(button("Family editor")).observe(
+            parent.spawn(button("main_menu.family_editor")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.set_state(GameState::FamilyEditor)
                 },
             );
-            parent.spawn(button("Exit")).observe(
+            parent.spawn(button("main_menu.credits")).observe(
+                |_on: On<Pointer<Click>>, mut commands: Commands| {
+                    commands.set_state(MenuState::Credits)
+                },
+            );
+            parent.spawn(button("main_menu.exit")).observe(
                 |_on: On<Pointer<Click>>, mut exit: MessageWriter<AppExit>| {
                     exit.write(AppExit::Success);
                 },
             );
+            parent.spawn((
+                Node {
+                    column_gap: GAP,
+                    ..Default::default()
+                },
+                ExclusiveGroup::default(),
+                Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
+                    for (code, name) in languages {
+                        let toggled = code == language;
+                        parent
+                            .spawn((
+                                Button,
+                                ButtonStyle::default(),
+                                Toggled(toggled),
+                                Text::new(name),
+                                TextFont::from_font_size(NORMAL_TEXT),
+                            ))
+                            .observe(
+                                move |_on: On<Pointer<Click>>,
+                                      mut commands: Commands,
+                                      mut settings: ResMut<Settings>| {
+                                    info!("changing language to '{code}'");
+                                    settings.locale.language = code.clone();
+                                    commands.trigger(SaveSettings);
+                                },
+                            );
+                    }
+                })),
+            ));
         })),
     ));
 }
 
-fn button(text: &str) -> impl Bundle {
+fn button(key: &'static str) -> impl Bundle {
     (
         Button,
         ButtonStyle::default(),
-        Text::new(text),
+        LocalizedText(key),
         TextFont::from_font_size(HUGE_TEXT),
     )
 }
@@ -0,0 +1,161 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use bevy::{
+    asset::{UntypedAssetId, io::AssetSourceId},
+    ecs::relationship::RelatedSpawner,
+    input::mouse::MouseScrollUnit,
+    prelude::*,
+};
+use simgine_core::{
+    asset_manifest::{
+        ManifestInfo, appearance::AppearanceManifest, object::ObjectManifest,
+        surface::SurfaceManifest,
+    },
+    localization::Localization,
+};
+
+use crate::{
+    menu::MenuState,
+    widget::{
+        button::style::ButtonStyle,
+        localized_text::LocalizedText,
+        theme::{GAP, HUGE_TEXT, LARGE_TEXT, SCREEN_OFFSET, SMALL_TEXT},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(MenuState::Credits), spawn);
+}
+
+fn spawn(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    objects: Res<Assets<ObjectManifest>>,
+    appearances: Res<Assets<AppearanceManifest>>,
+    surfaces: Res<Assets<SurfaceManifest>>,
+) {
+    info!("entering credits");
+
+    let manifests = objects
+        .iter()
+        .map(|(id, manifest)| (id.untyped(), &manifest.info))
+        .chain(
+            appearances
+                .iter()
+                .map(|(id, manifest)| (id.untyped(), &manifest.info)),
+        )
+        .chain(
+            surfaces
+                .iter()
+                .map(|(id, manifest)| (id.untyped(), &manifest.info)),
+        );
+    let credits = group_credits(manifests, &asset_server, &localization);
+    debug!("showing credits for {} authors", credits.len());
+
+    commands
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_self: JustifySelf::Center,
+                margin: SCREEN_OFFSET,
+                row_gap: GAP,
+                ..Default::default()
+            },
+            DespawnOnExit(MenuState::Credits),
+            children![(
+                Node {
+                    align_self: AlignSelf::Center,
+                    ..Default::default()
+                },
+                LocalizedText("credits.title"),
+                TextFont::from_font_size(HUGE_TEXT),
+            )],
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    max_height: Val::Vh(70.0),
+                    overflow: Overflow::scroll_y(),
+                    ..Default::default()
+                })
+                .observe(scroll)
+                .with_children(|parent| {
+                    for (author, licenses) in credits {
+                        parent.spawn((Text::new(author), TextFont::from_font_size(LARGE_TEXT)));
+                        for (license, names) in licenses {
+                            parent.spawn((
+                                Text::new(format!("{license}: {}", names.join(", "))),
+                                TextFont::from_font_size(SMALL_TEXT),
+                            ));
+                        }
+                    }
+                });
+        });
+    commands.spawn((
+        Node {
+            align_self: AlignSelf::End,
+            margin: SCREEN_OFFSET,
+            ..Default::default()
+        },
+        DespawnOnExit(MenuState::Credits),
+        Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
+            parent
+                .spawn((
+                    Button,
+                    LocalizedText("credits.back"),
+                    TextFont::from_font_size(LARGE_TEXT),
+                    ButtonStyle::default(),
+                ))
+                .observe(|_on: On<Pointer<Click>>, mut commands: Commands| {
+                    commands.set_state(MenuState::MainMenu)
+                });
+        })),
+    ));
+}
+
+fn scroll(scroll: On<Pointer<Scroll>>, mut positions: Query<&mut ScrollPosition>) {
+    let mut position = positions.get_mut(scroll.entity).unwrap();
+    let delta = match scroll.unit {
+        MouseScrollUnit::Line => scroll.y * LINE_HEIGHT,
+        MouseScrollUnit::Pixel => scroll.y,
+    };
+
+    // Clamped to the content size by the layout.
+    position.y -= delta;
+}
+
+const LINE_HEIGHT: f32 = 32.0;
+
+/// Groups translated manifest names by author and license.
+///
+/// Names of manifests from mods are suffixed with the mod name.
+fn group_credits<'a>(
+    manifests: impl Iterator<Item = (UntypedAssetId, &'a ManifestInfo)>,
+    asset_server: &AssetServer,
+    localization: &Localization,
+) -> BTreeMap<&'a str, BTreeMap<&'a str, Vec<String>>> {
+    let mut credits: BTreeMap<_, BTreeMap<_, Vec<_>>> = BTreeMap::new();
+    for (id, info) in manifests {
+        let mut name = localization.get(&info.name).to_string();
+        if let Some(path) = asset_server.get_path(id)
+            && let AssetSourceId::Name(mod_name) = path.source()
+        {
+            write!(name, " ({mod_name})").unwrap();
+        }
+
+        credits
+            .entry(info.author.as_str())
+            .or_default()
+            .entry(info.license.as_str())
+            .or_default()
+            .push(name);
+    }
+
+    for names in credits.values_mut().flat_map(BTreeMap::values_mut) {
+        names.sort();
+    }
+
+    credits
+}
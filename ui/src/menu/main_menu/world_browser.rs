@@ -14,6 +14,7 @@ use crate::{
     widget::{
         button::style::ButtonStyle,
         dialog::{dialog, dialog_button, dialog_close_button, dialog_title},
+        localized_text::LocalizedText,
         text_edit::text_edit,
         theme::{GAP, HUGE_TEXT, LARGE_TEXT, NORMAL_TEXT, SCREEN_OFFSET},
     },
@@ -42,7 +43,7 @@ fn spawn(mut commands: Commands) {
                     align_self: AlignSelf::Center,
                     ..Default::default()
                 },
-                Text::new("World browser"),
+                LocalizedText("world_browser.title"),
                 TextFont::from_font_size(HUGE_TEXT),
             ),
             world_nodes(),
@@ -56,7 +57,7 @@ fn spawn(mut commands: Commands) {
         },
         DespawnOnExit(MenuState::WorldBrowser),
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-            parent.spawn(bottom_button("Back")).observe(
+            parent.spawn(bottom_button("world_browser.back")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.set_state(MenuState::MainMenu)
                 },
@@ -73,12 +74,12 @@ fn spawn(mut commands: Commands) {
         },
         DespawnOnExit(MenuState::WorldBrowser),
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-            parent.spawn(bottom_button("Create")).observe(
+            parent.spawn(bottom_button("world_browser.create")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.spawn(create_dialog());
                 },
             );
-            parent.spawn(bottom_button("Join")).observe(
+            parent.spawn(bottom_button("world_browser.join")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.spawn(join_dialog());
                 },
@@ -87,10 +88,10 @@ fn spawn(mut commands: Commands) {
     ));
 }
 
-fn bottom_button(text: &str) -> impl Bundle {
+fn bottom_button(key: &'static str) -> impl Bundle {
     (
         Button,
-        Text::new(text),
+        LocalizedText(key),
         TextFont::from_font_size(LARGE_TEXT),
         ButtonStyle::default(),
     )
@@ -101,7 +102,7 @@ fn create_dialog() -> impl Bundle {
         dialog(),
         DespawnOnExit(MenuState::WorldBrowser),
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-            parent.spawn(dialog_title("Create world"));
+            parent.spawn(dialog_title("create_world.title"));
             let _name_edit = parent.spawn(text_edit("")).id();
             let name_edit = parent.spawn(text_edit("")).id();
             parent.spawn((
@@ -111,8 +112,8 @@ fn create_dialog() -> impl Bundle {
                     ..Default::default()
                 },
                 Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
-                    parent.spawn(dialog_close_button("Cancel"));
-                    parent.spawn(dialog_button("Create")).observe(
+                    parent.spawn(dialog_close_button("dialog.cancel"));
+                    parent.spawn(dialog_button("create_world.create")).observe(
                         move |_on: On<Pointer<Click>>,
                               mut commands: Commands,
                               texts: Query<&EditableText>| {
@@ -133,8 +134,11 @@ fn join_dialog() -> impl Bundle {
         dialog(),
         DespawnOnExit(MenuState::WorldBrowser),
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-            parent.spawn(dialog_title("Join game"));
-            parent.spawn((Text::new("Address"), TextFont::from_font_size(NORMAL_TEXT)));
+            parent.spawn(dialog_title("join_game.title"));
+            parent.spawn((
+                LocalizedText("join_game.address"),
+                TextFont::from_font_size(NORMAL_TEXT),
+            ));
             let addr_edit = parent
                 .spawn(text_edit(format!("127.0.0.1:{DEFAULT_PORT}")))
                 .id();
@@ -163,9 +167,9 @@ fn join_dialog() -> impl Bundle {
                     ..Default::default()
                 })
                 .with_children(|parent: &mut RelatedSpawner<_>| {
-                    parent.spawn(dialog_close_button("Cancel"));
+                    parent.spawn(dialog_close_button("dialog.cancel"));
                     parent
-                        .spawn(dialog_button("Connect"))
+                        .spawn(dialog_button("join_game.connect"))
                         .observe(connect.pipe(trigger_error));
                 });
         })),
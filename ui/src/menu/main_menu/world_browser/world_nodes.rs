@@ -3,6 +3,7 @@ use simgine_core::{error_event::trigger_error, game_paths::GamePaths, world::Loa
 
 use crate::widget::{
     button::style::ButtonStyle,
+    localized_text::LocalizedText,
     theme::{GAP, INNER_RADIUS, OUTER_RADIUS, RADIUS_GAP, SHADOW, SMALL_TEXT},
 };
 
@@ -58,7 +59,7 @@ fn spawn(
                             ..Default::default()
                         },
                         Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
-                            parent.spawn(world_button("Play")).observe(
+                            parent.spawn(world_button("world_browser.play")).observe(
                                 move |_on: On<Pointer<Click>>,
                                       mut commands: Commands,
                                       labels: Query<&Text>| {
@@ -81,7 +82,7 @@ fn spawn(
                                 Ok(())
                             };
                             parent
-                                .spawn(world_button("Delete"))
+                                .spawn(world_button("world_browser.delete"))
                                 .observe(remove_world.pipe(trigger_error));
                         })),
                     ));
@@ -104,10 +105,10 @@ pub(super) fn world_nodes() -> impl Bundle {
     )
 }
 
-fn world_button(text: &str) -> impl Bundle {
+fn world_button(key: &'static str) -> impl Bundle {
     (
         Button,
-        Text::new(text),
+        LocalizedText(key),
         TextFont::from_font_size(SMALL_TEXT),
         ButtonStyle::BLACK,
         Node {
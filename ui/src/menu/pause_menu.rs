@@ -8,7 +8,7 @@ use simgine_core::{
     world::{
         SaveWorld,
         cursor::caster::CursorCastDisabler,
-        time::speed::{Paused, SetPaused},
+        time::speed::{Paused, SetPaused, SpeedPolicy},
     },
 };
 
@@ -49,25 +49,27 @@ fn open(_on: On<Fire<OpenPauseMenu>>, mut commands: Commands) {
         DespawnOnExit(GameState::World),
         Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
             let dialog = parent.target_entity();
-            parent.spawn(dialog_title("Menu"));
-            parent.spawn(dialog_close_button("Resume"));
-            parent.spawn(dialog_button("Save")).observe(
+            parent.spawn(dialog_title("pause_menu.title"));
+            parent.spawn(dialog_close_button("pause_menu.resume"));
+            parent.spawn(dialog_button("pause_menu.save")).observe(
                 move |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.trigger(SaveWorld);
                     commands.entity(dialog).despawn();
                 },
             );
-            parent.spawn(dialog_button("Multiplayer")).observe(
-                |_on: On<Pointer<Click>>, mut commands: Commands| {
-                    commands.spawn(multiplayer_menu());
-                },
-            );
-            parent.spawn(dialog_button("Main menu")).observe(
+            parent
+                .spawn(dialog_button("pause_menu.multiplayer"))
+                .observe(
+                    |_on: On<Pointer<Click>>, mut commands: Commands, policy: Res<SpeedPolicy>| {
+                        commands.spawn(multiplayer_menu(*policy));
+                    },
+                );
+            parent.spawn(dialog_button("pause_menu.main_menu")).observe(
                 |_on: On<Pointer<Click>>, mut commands: Commands| {
                     commands.set_state(GameState::Menu);
                 },
             );
-            parent.spawn(dialog_button("Exit")).observe(
+            parent.spawn(dialog_button("pause_menu.exit")).observe(
                 |_on: On<Pointer<Click>>, mut exit: MessageWriter<AppExit>| {
                     exit.write(AppExit::Success);
                 },
@@ -4,11 +4,13 @@ use simgine_core::{
     error_event::trigger_error,
     network::{DEFAULT_PORT, Host, StopServer},
     state::GameState,
+    world::time::speed::SpeedPolicy,
 };
 
 use crate::widget::{
-    button::style::ButtonStyle,
+    button::{exclusive_group::ExclusiveGroup, style::ButtonStyle, toggled::Toggled},
     dialog::{dialog, dialog_close_button, dialog_title},
+    localized_text::LocalizedText,
     text_edit::text_edit,
     theme::{GAP, NORMAL_TEXT},
 };
@@ -18,24 +20,29 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 fn update_start_stop(
-    mut text: Single<&mut Text, With<StartStopButton>>,
+    mut commands: Commands,
+    button: Single<(Entity, Ref<StartStopButton>)>,
     server_state: Res<State<ServerState>>,
 ) {
-    if text.is_changed() || server_state.is_changed() {
-        text.clear();
-        match **server_state {
-            ServerState::Stopped => text.push_str("Start"),
-            ServerState::Running => text.push_str("Stop"),
-        }
+    let (entity, button) = *button;
+    if button.is_added() || server_state.is_changed() {
+        let key = match **server_state {
+            ServerState::Stopped => "multiplayer.start",
+            ServerState::Running => "multiplayer.stop",
+        };
+        commands.entity(entity).insert(LocalizedText(key));
     }
 }
 
-pub(super) fn multiplayer_menu() -> impl Bundle {
+/// Dialog to start or stop the server.
+///
+/// `policy` is the currently active [`SpeedPolicy`] to display as selected.
+pub(super) fn multiplayer_menu(policy: SpeedPolicy) -> impl Bundle {
     (
         dialog(),
         DespawnOnExit(GameState::World),
         children![
-            dialog_title("Multiplayer"),
+            dialog_title("multiplayer.title"),
             (
                 Node {
                     column_gap: GAP,
@@ -43,7 +50,10 @@ pub(super) fn multiplayer_menu() -> impl Bundle {
                     ..Default::default()
                 },
                 Children::spawn(SpawnWith(|parent: &mut RelatedSpawner<_>| {
-                    parent.spawn((Text::new("Port"), TextFont::from_font_size(NORMAL_TEXT)));
+                    parent.spawn((
+                        LocalizedText("multiplayer.port"),
+                        TextFont::from_font_size(NORMAL_TEXT),
+                    ));
                     let port_edit = parent.spawn(text_edit(DEFAULT_PORT.to_string())).id();
                     let start_stop = move |_on: On<Pointer<Click>>,
                                            mut commands: Commands,
@@ -75,7 +85,49 @@ pub(super) fn multiplayer_menu() -> impl Bundle {
                         .observe(start_stop.pipe(trigger_error));
                 })),
             ),
-            dialog_close_button("Close")
+            (
+                Node {
+                    column_gap: GAP,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
+                    parent.spawn((
+                        LocalizedText("multiplayer.speed_policy"),
+                        TextFont::from_font_size(NORMAL_TEXT),
+                    ));
+                    parent.spawn((
+                        Node {
+                            column_gap: GAP,
+                            ..Default::default()
+                        },
+                        ExclusiveGroup::default(),
+                        Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<_>| {
+                            for (button_policy, key) in [
+                                (SpeedPolicy::HostOnly, "multiplayer.host_only"),
+                                (SpeedPolicy::Majority, "multiplayer.majority"),
+                                (SpeedPolicy::Slowest, "multiplayer.slowest"),
+                            ] {
+                                parent
+                                    .spawn((
+                                        Button,
+                                        ButtonStyle::default(),
+                                        Toggled(button_policy == policy),
+                                        LocalizedText(key),
+                                        TextFont::from_font_size(NORMAL_TEXT),
+                                    ))
+                                    .observe(
+                                        move |_on: On<Pointer<Click>>, mut commands: Commands| {
+                                            info!("setting speed policy to `{button_policy:?}`");
+                                            commands.insert_resource(button_policy);
+                                        },
+                                    );
+                            }
+                        })),
+                    ));
+                })),
+            ),
+            dialog_close_button("dialog.close")
         ],
     )
 }
@@ -9,6 +9,7 @@ use bevy::{
     color::palettes::tailwind::GRAY_400,
     light::light_consts::lux,
     pbr::wireframe::NoWireframe,
+    platform::collections::HashMap,
     prelude::*,
     render::render_resource::TextureFormat,
 };
@@ -16,7 +17,9 @@ use simgine_core::asset_manifest::object::ObjectManifest;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<ThumbnailState>()
+        .init_resource::<ThumbnailCache>()
         .add_systems(Startup, setup)
+        .add_systems(Update, invalidate_cache)
         .add_systems(OnEnter(ThumbnailState::Inactive), despawn_asset)
         .add_systems(OnEnter(ThumbnailState::Warmup), warmup)
         .add_systems(OnEnter(ThumbnailState::Rendering), render)
@@ -64,14 +67,43 @@ fn setup(mut commands: Commands) {
     ));
 }
 
+/// Removes cached thumbnails for manifests that were modified on disk.
+fn invalidate_cache(
+    mut asset_events: MessageReader<AssetEvent<ObjectManifest>>,
+    mut cache: ResMut<ThumbnailCache>,
+) {
+    for &event in asset_events.read() {
+        if let AssetEvent::Modified { id } | AssetEvent::Removed { id } = event
+            && cache.remove(&id).is_some()
+        {
+            debug!("invalidating thumbnail for `{id:?}`");
+        }
+    }
+}
+
 fn wait_for_request(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     manifests: Res<Assets<ObjectManifest>>,
-    thumbnails: Query<(Entity, &Thumbnail, Has<CalculatedClip>), Without<ThumbnailProcessed>>,
+    cache: Res<ThumbnailCache>,
+    mut thumbnails: Query<
+        (Entity, &Thumbnail, &mut ImageNode, Has<CalculatedClip>),
+        Without<ThumbnailProcessed>,
+    >,
 ) {
+    for (target, thumbnail, mut node, _) in &mut thumbnails {
+        if let Some(image) = cache.get(&**thumbnail) {
+            debug!("reusing cached thumbnail for `{:?}`", **thumbnail);
+            node.image = image.clone();
+            commands.entity(target).insert(ThumbnailProcessed);
+        }
+    }
+
     // Check for `CalculatedClip` to make sure that the thumbnail node is visible.
-    let Some((target, &thumbnail, ..)) = thumbnails.iter().find(|&(.., c)| !c) else {
+    let Some((target, &thumbnail, ..)) = thumbnails
+        .iter()
+        .find(|&(_, thumbnail, _, c)| !c && !cache.contains_key(&**thumbnail))
+    else {
         return;
     };
 
@@ -186,20 +218,22 @@ fn render(mut commands: Commands, mut camera: Single<&mut Camera, With<Thumbnail
 
 fn despawn_asset(
     mut commands: Commands,
+    mut cache: ResMut<ThumbnailCache>,
     camera: Single<(&mut Camera, &RenderTarget), With<ThumbnailCamera>>,
     thumbnail_asset: Single<(Entity, &ThumbnailOf)>,
-    mut nodes: Query<&mut ImageNode>,
+    mut nodes: Query<(&Thumbnail, &mut ImageNode)>,
 ) {
     let (mut camera, render_target) = camera.into_inner();
     camera.is_active = false;
 
     let (entity, &thumbnail_of) = *thumbnail_asset;
-    if let Ok(mut node) = nodes.get_mut(*thumbnail_of) {
+    if let Ok((thumbnail, mut node)) = nodes.get_mut(*thumbnail_of) {
         debug!("assigning image to node");
         let image = render_target
             .as_image()
             .expect("thumbnail camera should render only to images");
         node.image = image.clone();
+        cache.insert(**thumbnail, image.clone());
     } else {
         debug!("thumbnail target is no longer valid");
     }
@@ -227,6 +261,12 @@ enum ThumbnailState {
 #[require(ImageNode)]
 pub(crate) struct Thumbnail(pub(crate) AssetId<ObjectManifest>);
 
+/// Rendered thumbnails for object manifests.
+///
+/// Reused when nodes are respawned, for example when switching catalog pages.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ThumbnailCache(HashMap<AssetId<ObjectManifest>, Handle<Image>>);
+
 /// Marks entity with [`Thumbnail`] as processed end excludes it from thumbnail generation.
 #[derive(Component)]
 struct ThumbnailProcessed;
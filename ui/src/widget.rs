@@ -1,8 +1,10 @@
 pub(crate) mod button;
 pub(crate) mod dialog;
 pub(crate) mod focus;
+pub(crate) mod localized_text;
 pub(crate) mod text_edit;
 pub(crate) mod theme;
+pub(crate) mod tooltip;
 
 use bevy::prelude::*;
 
@@ -11,6 +13,8 @@ pub(super) fn plugin(app: &mut App) {
         button::plugin,
         dialog::plugin,
         focus::plugin,
+        localized_text::plugin,
         text_edit::plugin,
+        tooltip::plugin,
     ));
 }
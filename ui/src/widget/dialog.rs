@@ -3,6 +3,7 @@ use bevy_enhanced_input::prelude::*;
 
 use super::{
     button::{action::ButtonContext, style::ButtonStyle},
+    localized_text::LocalizedText,
     theme::{GAP, LARGE_TEXT, NORMAL_TEXT, OUTER_RADIUS, PADDING, SMALL_TEXT},
 };
 use crate::{button_bindings, widget::button::action::Activate};
@@ -49,27 +50,27 @@ pub(crate) fn dialog() -> impl Bundle {
     )
 }
 
-pub(crate) fn dialog_title(text: &str) -> impl Bundle {
-    (Text::new(text), TextFont::from_font_size(LARGE_TEXT))
+pub(crate) fn dialog_title(key: &'static str) -> impl Bundle {
+    (LocalizedText(key), TextFont::from_font_size(LARGE_TEXT))
 }
 
 pub(crate) fn dialog_text(text: impl Into<String>) -> impl Bundle {
     (Text::new(text), TextFont::from_font_size(SMALL_TEXT))
 }
 
-pub(crate) fn dialog_close_button(text: &str) -> impl Bundle {
+pub(crate) fn dialog_close_button(key: &'static str) -> impl Bundle {
     (
-        dialog_button(text),
+        dialog_button(key),
         CloseButton,
         ButtonContext,
         button_bindings![KeyCode::Escape],
     )
 }
 
-pub(crate) fn dialog_button(text: &str) -> impl Bundle {
+pub(crate) fn dialog_button(key: &'static str) -> impl Bundle {
     (
         Button,
-        Text::new(text),
+        LocalizedText(key),
         TextFont::from_font_size(NORMAL_TEXT),
         ButtonStyle::default(),
         Node {
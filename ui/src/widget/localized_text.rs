@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+use simgine_core::localization::Localization;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(init)
+        .add_systems(PostUpdate, update.run_if(resource_changed::<Localization>));
+}
+
+fn init(
+    insert: On<Insert, LocalizedText>,
+    localization: Res<Localization>,
+    mut texts: Query<(&LocalizedText, &mut Text)>,
+) {
+    let (localized, mut text) = texts.get_mut(insert.entity).unwrap();
+    **text = localization.get(localized).to_string();
+}
+
+fn update(localization: Res<Localization>, mut texts: Query<(&LocalizedText, &mut Text)>) {
+    debug!("updating localized texts");
+    for (localized, mut text) in &mut texts {
+        **text = localization.get(localized).to_string();
+    }
+}
+
+/// Displays translation for the key from [`Localization`].
+#[derive(Component, Deref, Clone, Copy)]
+#[component(immutable)]
+#[require(Text)]
+pub(crate) struct LocalizedText(pub(crate) &'static str);
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use super::theme::{INNER_RADIUS, RADIUS_GAP, SMALL_TEXT};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(show).add_observer(hide);
+}
+
+fn show(over: On<Pointer<Over>>, mut commands: Commands, tooltips: Query<&Tooltip>) {
+    let Ok(tooltip) = tooltips.get(over.entity) else {
+        return;
+    };
+
+    debug!("showing tooltip for `{}`", over.entity);
+    // Spawned as a child to be despawned together with the entity.
+    commands.entity(over.entity).with_child((
+        TooltipNode,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(100.0),
+            left: Val::ZERO,
+            padding: RADIUS_GAP,
+            border_radius: INNER_RADIUS,
+            ..Default::default()
+        },
+        BackgroundColor(Color::BLACK),
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        Text::new(&**tooltip),
+        TextFont::from_font_size(SMALL_TEXT),
+    ));
+}
+
+fn hide(
+    out: On<Pointer<Out>>,
+    mut commands: Commands,
+    tooltips: Query<&Children, With<Tooltip>>,
+    nodes: Query<Entity, With<TooltipNode>>,
+) {
+    let Ok(children) = tooltips.get(out.entity) else {
+        return;
+    };
+
+    for node in nodes.iter_many(children) {
+        debug!("hiding tooltip for `{}`", out.entity);
+        commands.entity(node).despawn();
+    }
+}
+
+/// Text displayed under the entity while it's hovered.
+#[derive(Component, Deref)]
+pub(crate) struct Tooltip(pub(crate) String);
+
+#[derive(Component)]
+struct TooltipNode;